@@ -0,0 +1,316 @@
+//! Derive macro for mapping domain errors into `error_envelope::Error`.
+//!
+//! Use it through the `derive` feature of `error-envelope`:
+//!
+//! ```toml
+//! [dependencies]
+//! error-envelope = { version = "0.3", features = ["derive"] }
+//! ```
+//!
+//! See [`IntoEnvelope`] for the supported attributes.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, LitBool, LitInt, LitStr, Path,
+};
+
+/// Built-in `Code` variants that may be named without a path.
+const BUILTIN_CODES: &[&str] = &[
+    "Internal",
+    "BadRequest",
+    "NotFound",
+    "MethodNotAllowed",
+    "Gone",
+    "Conflict",
+    "PayloadTooLarge",
+    "UnsupportedMediaType",
+    "RequestTimeout",
+    "RateLimited",
+    "Unavailable",
+    "ValidationFailed",
+    "Unauthorized",
+    "Forbidden",
+    "UnprocessableEntity",
+    "Timeout",
+    "Canceled",
+    "DownstreamError",
+    "DownstreamTimeout",
+];
+
+/// Generates `impl From<T> for error_envelope::Error`.
+///
+/// Each enum variant (or the struct itself) takes an `#[envelope(...)]`
+/// attribute. An attribute on the enum applies to variants without their own.
+///
+/// | Key | Meaning |
+/// |-----|---------|
+/// | `code = NotFound` | Built-in `Code` variant, or a path to a `CustomCode` constant |
+/// | `status = 410` | Overrides the code's default status |
+/// | `message = "user {id} not found"` | Format string over the variant's fields (`{0}` for tuple fields) |
+/// | `retryable` / `retryable = false` | Overrides the code's default retryability |
+/// | `details(id, name)` | Serializes the listed named fields into `details` |
+///
+/// A field marked `#[envelope(cause)]`, `#[source]` or `#[from]` is attached
+/// as the error's cause.
+///
+/// ```ignore
+/// use error_envelope::IntoEnvelope;
+///
+/// #[derive(Debug, IntoEnvelope)]
+/// enum UserError {
+///     #[envelope(code = NotFound, message = "user {id} not found", details(id))]
+///     NotFound { id: u64 },
+///
+///     #[envelope(code = Internal, message = "database failure")]
+///     Database(#[envelope(cause)] std::io::Error),
+/// }
+/// ```
+#[proc_macro_derive(IntoEnvelope, attributes(envelope))]
+pub fn derive_into_envelope(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Parsed `#[envelope(...)]` attribute.
+#[derive(Clone, Default)]
+struct Mapping {
+    code: Option<Path>,
+    status: Option<LitInt>,
+    message: Option<LitStr>,
+    retryable: Option<bool>,
+    details: Vec<Ident>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let default = parse_mapping(&input.attrs)?;
+
+    let arms = match &input.data {
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let mapping = match parse_mapping(&variant.attrs)? {
+                    Some(mapping) => mapping,
+                    None => match &default {
+                        Some(mapping) => mapping.clone(),
+                        None => {
+                            return Err(syn::Error::new_spanned(
+                                variant,
+                                "missing #[envelope(code = ...)] attribute",
+                            ))
+                        }
+                    },
+                };
+                let ident = &variant.ident;
+                expand_arm(quote!(#name::#ident), &variant.fields, &mapping)
+            })
+            .collect::<syn::Result<Vec<_>>>()?,
+        Data::Struct(data) => {
+            let mapping = default.ok_or_else(|| {
+                syn::Error::new_spanned(&input.ident, "missing #[envelope(code = ...)] attribute")
+            })?;
+            vec![expand_arm(quote!(#name), &data.fields, &mapping)?]
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "IntoEnvelope cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for ::error_envelope::Error
+        #where_clause
+        {
+            #[allow(unused_variables)]
+            fn from(value: #name #ty_generics) -> Self {
+                match value {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+fn expand_arm(path: TokenStream2, fields: &Fields, mapping: &Mapping) -> syn::Result<TokenStream2> {
+    let mut cause = None;
+    let bindings: Vec<Ident> = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let binding = match &field.ident {
+                Some(ident) => ident.clone(),
+                None => format_ident!("_{}", index),
+            };
+            if is_cause(&field.attrs)? {
+                if cause.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "only one field can be the cause",
+                    ));
+                }
+                cause = Some(binding.clone());
+            }
+            Ok(binding)
+        })
+        .collect::<syn::Result<_>>()?;
+
+    let pattern = match fields {
+        Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => quote!(#path),
+    };
+
+    let code = match &mapping.code {
+        Some(code) => code_expr(code),
+        None => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "missing `code = ...` in #[envelope] attribute",
+            ))
+        }
+    };
+
+    let status = match &mapping.status {
+        Some(status) => quote!(#status),
+        None => quote!(0),
+    };
+
+    let message = match &mapping.message {
+        Some(message) if message.value().contains('{') => {
+            let message = LitStr::new(&positional_to_bindings(&message.value()), message.span());
+            quote!(::std::format!(#message))
+        }
+        Some(message) => quote!(#message),
+        None => quote!(""),
+    };
+
+    // Not `err`: the envelope shares a scope with the variant's field bindings
+    let err = format_ident!("__envelope_err");
+    let mut steps = Vec::new();
+    if let Some(retryable) = mapping.retryable {
+        steps.push(quote!(let #err = #err.with_retryable(#retryable);));
+    }
+    if !mapping.details.is_empty() {
+        let keys = mapping.details.iter().map(|ident| ident.to_string());
+        let values = &mapping.details;
+        steps.push(quote! {
+            let #err = #err.with_details(::error_envelope::__private::serde_json::json!({
+                #(#keys: #values),*
+            }));
+        });
+    }
+    if let Some(cause) = cause {
+        steps.push(quote!(let #err = ::error_envelope::__private::with_cause(#err, #cause);));
+    }
+
+    Ok(quote! {
+        #pattern => {
+            let #err = ::error_envelope::Error::new(#code, #status, #message);
+            #(#steps)*
+            #err
+        }
+    })
+}
+
+/// Resolves `code = NotFound` to the built-in variant and anything else to
+/// a `CustomCode` (or `Code`) expression.
+fn code_expr(code: &Path) -> TokenStream2 {
+    if let Some(ident) = code.get_ident() {
+        if BUILTIN_CODES.contains(&ident.to_string().as_str()) {
+            return quote!(::error_envelope::Code::#ident);
+        }
+    }
+    quote!(::error_envelope::Code::from(#code))
+}
+
+/// Rewrites `{0}` placeholders to the `_0` bindings used for tuple fields.
+fn positional_to_bindings(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(ch) = chars.next() {
+        out.push(ch);
+        if ch != '{' {
+            continue;
+        }
+        match chars.peek() {
+            Some('{') => out.push(chars.next().unwrap()),
+            Some(next) if next.is_ascii_digit() => out.push('_'),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn is_cause(attrs: &[Attribute]) -> syn::Result<bool> {
+    for attr in attrs {
+        if attr.path().is_ident("source") || attr.path().is_ident("from") {
+            return Ok(true);
+        }
+        if attr.path().is_ident("envelope") {
+            let mut cause = false;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("cause") {
+                    cause = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `cause`"))
+                }
+            })?;
+            if cause {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn parse_mapping(attrs: &[Attribute]) -> syn::Result<Option<Mapping>> {
+    let mut result = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("envelope")) {
+        if result.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate #[envelope] attribute",
+            ));
+        }
+
+        let mut mapping = Mapping::default();
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                mapping.code = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("status") {
+                mapping.status = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("message") {
+                mapping.message = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("retryable") {
+                mapping.retryable = Some(if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<LitBool>()?.value
+                } else {
+                    true
+                });
+            } else if meta.path.is_ident("details") {
+                meta.parse_nested_meta(|field| {
+                    if let Some(ident) = field.path.get_ident() {
+                        mapping.details.push(ident.clone());
+                        return Ok(());
+                    }
+                    Err(field.error("expected a field name"))
+                })?;
+            } else {
+                return Err(meta
+                    .error("expected one of `code`, `status`, `message`, `retryable`, `details`"));
+            }
+            Ok(())
+        })?;
+        result = Some(mapping);
+    }
+    Ok(result)
+}
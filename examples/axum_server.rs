@@ -11,10 +11,12 @@
 //! curl http://localhost:3000/user?id=
 //! curl http://localhost:3000/rate-limit
 //! curl http://localhost:3000/validation
+//! curl http://localhost:3000/missing
+//! curl -X POST http://localhost:3000/user
 //! ```
 
 use axum::{extract::Query, routing::get, Router};
-use error_envelope::{Error, FieldErrors};
+use error_envelope::{axum_support::RouterExt, Error, FieldErrors, Violation};
 use serde::Deserialize;
 use std::time::Duration;
 
@@ -40,8 +42,12 @@ async fn rate_limit_example() -> Result<String, Error> {
 
 async fn validation_example() -> Result<String, Error> {
     let mut fields = FieldErrors::new();
-    fields.insert("email".to_string(), "must be a valid email".to_string());
-    fields.insert("age".to_string(), "must be 18 or older".to_string());
+    fields.add("email", "invalid_format", "must be a valid email");
+    fields.push(
+        Violation::new("age", "must be 18 or older")
+            .with_code("range")
+            .with_param("min", 18),
+    );
 
     Err(error_envelope::validation(fields).with_trace_id("validation-error-456"))
 }
@@ -57,7 +63,8 @@ async fn main() {
         .route("/user", get(get_user))
         .route("/rate-limit", get(rate_limit_example))
         .route("/validation", get(validation_example))
-        .route("/downstream", get(downstream_example));
+        .route("/downstream", get(downstream_example))
+        .envelope_fallbacks();
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
         .await
@@ -70,6 +77,8 @@ async fn main() {
     println!("  curl http://localhost:3000/rate-limit");
     println!("  curl http://localhost:3000/validation");
     println!("  curl http://localhost:3000/downstream");
+    println!("  curl http://localhost:3000/missing");
+    println!("  curl -X POST http://localhost:3000/user");
 
     axum::serve(listener, app).await.unwrap();
 }
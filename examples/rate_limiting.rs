@@ -27,12 +27,13 @@ impl RateLimiter {
         }
     }
 
+    #[allow(clippy::result_large_err)]
     fn check(&self, user_id: &str) -> Result<(), Error> {
         let mut requests = self.requests.lock().unwrap();
         let now = Instant::now();
 
         // Get or create request history for this user
-        let history = requests.entry(user_id.to_string()).or_insert_with(Vec::new);
+        let history = requests.entry(user_id.to_string()).or_default();
 
         // Remove old requests outside the time window
         history.retain(|&time| now.duration_since(time) < self.window);
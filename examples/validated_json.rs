@@ -0,0 +1,70 @@
+/// Example: Request validation with the validator crate
+///
+/// This demonstrates `ValidatedJson`, which deserializes and validates a
+/// body in one step, rejecting with a VALIDATION_FAILED envelope that lists
+/// every failed check (including nested structs and lists).
+///
+/// Run with: cargo run --example validated_json --features axum-support,validator-support
+use axum::{routing::post, Json, Router};
+use error_envelope::{validator_support::ValidatedJson, Error};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+struct Address {
+    #[validate(length(min = 5, max = 10, message = "must be 5 to 10 characters"))]
+    zip: String,
+}
+
+#[derive(Deserialize, Validate)]
+struct CreateUserRequest {
+    #[validate(email(message = "must be a valid email address"))]
+    email: String,
+    #[validate(length(min = 8, message = "must be at least 8 characters"))]
+    password: String,
+    #[validate(range(min = 18, max = 120))]
+    age: u8,
+    #[validate(nested)]
+    address: Address,
+}
+
+#[derive(Serialize)]
+struct User {
+    id: String,
+    email: String,
+}
+
+async fn create_user(
+    ValidatedJson(req): ValidatedJson<CreateUserRequest>,
+) -> Result<Json<User>, Error> {
+    // Only valid requests get here
+    Ok(Json(User {
+        id: "user-456".to_string(),
+        email: req.email,
+    }))
+}
+
+#[tokio::main]
+async fn main() {
+    let app = Router::new().route("/user", post(create_user));
+
+    println!("Starting server on http://localhost:3000");
+    println!("\nTest validation errors:");
+    println!(
+        r#"  curl -X POST http://localhost:3000/user \
+    -H "Content-Type: application/json" \
+    -d '{{"email":"bad","password":"short","age":15,"address":{{"zip":"1"}}}}'"#
+    );
+
+    println!("\n  # Valid request:");
+    println!(
+        r#"  curl -X POST http://localhost:3000/user \
+    -H "Content-Type: application/json" \
+    -d '{{"email":"user@example.com","password":"secure123","age":25,"address":{{"zip":"94107"}}}}'"#
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
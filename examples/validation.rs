@@ -5,9 +5,8 @@
 ///
 /// Run with: cargo run --example validation --features axum-support
 use axum::{extract::Json, routing::post, Router};
-use error_envelope::{validation, Error};
+use error_envelope::{validation, Error, FieldErrors, Violation};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 #[derive(Deserialize)]
 struct CreateUserRequest {
@@ -15,6 +14,8 @@ struct CreateUserRequest {
     password: String,
     age: u8,
     username: String,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -25,50 +26,53 @@ struct User {
 }
 
 async fn create_user(Json(req): Json<CreateUserRequest>) -> Result<Json<User>, Error> {
-    // Collect all validation errors
-    let mut errors = HashMap::new();
+    // Collect all validation errors; a field can fail several checks
+    let mut errors = FieldErrors::new();
 
     // Email validation
     if !req.email.contains('@') {
-        errors.insert(
-            "email".to_string(),
-            "must be a valid email address".to_string(),
-        );
+        errors.add("email", "invalid_format", "must be a valid email address");
     }
     if req.email.len() < 5 {
-        errors.insert(
-            "email".to_string(),
-            "must be at least 5 characters".to_string(),
+        errors.push(
+            Violation::new("email", "must be at least 5 characters")
+                .with_code("too_short")
+                .with_param("min", 5),
         );
     }
 
     // Password validation
     if req.password.len() < 8 {
-        errors.insert(
-            "password".to_string(),
-            "must be at least 8 characters".to_string(),
+        errors.push(
+            Violation::new("password", "must be at least 8 characters")
+                .with_code("too_short")
+                .with_param("min", 8),
         );
     }
     if !req.password.chars().any(|c| c.is_numeric()) {
-        errors.insert(
-            "password".to_string(),
-            "must contain at least one number".to_string(),
+        errors.add(
+            "password",
+            "missing_digit",
+            "must contain at least one number",
         );
     }
 
     // Age validation
-    if req.age < 18 {
-        errors.insert("age".to_string(), "must be 18 or older".to_string());
-    }
-    if req.age > 120 {
-        errors.insert("age".to_string(), "must be a valid age".to_string());
+    if !(18..=120).contains(&req.age) {
+        errors.push(
+            Violation::new("age", "must be between 18 and 120")
+                .with_code("range")
+                .with_param("min", 18)
+                .with_param("max", 120),
+        );
     }
 
     // Username validation
     if req.username.len() < 3 {
-        errors.insert(
-            "username".to_string(),
-            "must be at least 3 characters".to_string(),
+        errors.push(
+            Violation::new("username", "must be at least 3 characters")
+                .with_code("too_short")
+                .with_param("min", 3),
         );
     }
     if !req
@@ -76,12 +80,20 @@ async fn create_user(Json(req): Json<CreateUserRequest>) -> Result<Json<User>, E
         .chars()
         .all(|c| c.is_alphanumeric() || c == '_')
     {
-        errors.insert(
-            "username".to_string(),
-            "can only contain letters, numbers, and underscores".to_string(),
+        errors.add(
+            "username",
+            "invalid_chars",
+            "can only contain letters, numbers, and underscores",
         );
     }
 
+    // Nested fields use dotted paths (or JSON Pointers like /tags/0)
+    for (i, tag) in req.tags.iter().enumerate() {
+        if tag.is_empty() {
+            errors.add(format!("tags[{}]", i), "required", "must not be empty");
+        }
+    }
+
     // Return validation error if any fields failed
     if !errors.is_empty() {
         return Err(validation(errors).with_trace_id("req-123"));
@@ -105,7 +117,7 @@ async fn main() {
     println!(
         r#"  curl -X POST http://localhost:3000/user \
     -H "Content-Type: application/json" \
-    -d '{{"email":"bad","password":"short","age":15,"username":"ab"}}'"#
+    -d '{{"email":"bad","password":"short","age":15,"username":"ab","tags":["ok",""]}}'"#
     );
 
     println!("\n  # Valid request:");
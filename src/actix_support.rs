@@ -0,0 +1,221 @@
+//! actix-web integration for error-envelope.
+//!
+//! Enable this module with the `actix-support` feature. `Error` implements
+//! `actix_web::ResponseError` with the same status, body and headers as
+//! `into_http_response`, so handlers can return `Result<T, Error>`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use actix_web::{web, App, HttpServer};
+//! use error_envelope::{actix_support, Error};
+//!
+//! async fn handler() -> Result<String, Error> {
+//!     Err(Error::not_found("User not found"))
+//! }
+//!
+//! #[actix_web::main]
+//! async fn main() -> std::io::Result<()> {
+//!     HttpServer::new(|| {
+//!         App::new()
+//!             // JSON, path, query and form errors as envelopes
+//!             .configure(actix_support::configure)
+//!             .route("/", web::get().to(handler))
+//!     })
+//!     .bind("127.0.0.1:3000")?
+//!     .run()
+//!     .await
+//! }
+//! ```
+
+use crate::rejection::{field_error, named_field, status_error};
+use crate::response::REQUEST_ID_HEADER;
+use crate::{default_format, default_redaction, Error};
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError, UrlencodedError};
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.response_status().as_u16())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.report();
+
+        let format = default_format();
+        let mut builder = HttpResponse::build(self.status_code());
+        builder.insert_header((header::CONTENT_TYPE, format.content_type()));
+
+        // Add Retry-After header if specified
+        if let Some(duration) = self.retry_after {
+            builder.insert_header((header::RETRY_AFTER, duration.as_secs().max(1)));
+        }
+
+        // Add X-Request-Id header if trace ID is present
+        if let Some(ref trace_id) = self.trace_id {
+            if let Ok(value) = header::HeaderValue::from_str(trace_id) {
+                builder.insert_header((REQUEST_ID_HEADER, value));
+            }
+        }
+
+        // Keep the original error for logging middleware
+        builder.extensions_mut().insert(self.clone());
+        builder.body(self.render_body(format, default_redaction(), None))
+    }
+}
+
+impl From<JsonPayloadError> for Error {
+    fn from(err: JsonPayloadError) -> Self {
+        let message = err.to_string();
+        let mapped = match &err {
+            JsonPayloadError::Deserialize(inner) if inner.is_data() => {
+                match named_field(&message) {
+                    Some(field) => field_error(field, inner.to_string(), message),
+                    None => Error::validation(message),
+                }
+            }
+            JsonPayloadError::ContentType => Error::unsupported_media_type(message),
+            _ => status_error(err.status_code().as_u16(), message),
+        };
+        mapped.with_source(err)
+    }
+}
+
+impl From<PathError> for Error {
+    fn from(err: PathError) -> Self {
+        let message = err.to_string();
+        let mapped = match (&err, named_field(&message)) {
+            (PathError::Deserialize(inner), Some(field)) => {
+                field_error(field, inner.to_string(), message)
+            }
+            _ => status_error(err.status_code().as_u16(), message),
+        };
+        mapped.with_source(err)
+    }
+}
+
+impl From<QueryPayloadError> for Error {
+    fn from(err: QueryPayloadError) -> Self {
+        let message = err.to_string();
+        let mapped = match (&err, named_field(&message)) {
+            (QueryPayloadError::Deserialize(inner), Some(field)) => {
+                field_error(field, inner.to_string(), message)
+            }
+            _ => status_error(err.status_code().as_u16(), message),
+        };
+        mapped.with_source(err)
+    }
+}
+
+impl From<UrlencodedError> for Error {
+    fn from(err: UrlencodedError) -> Self {
+        let message = err.to_string();
+        let mapped = match &err {
+            UrlencodedError::Parse(inner) => match named_field(&message) {
+                Some(field) => field_error(field, inner.to_string(), message),
+                None => Error::bad_request(message),
+            },
+            _ => status_error(err.status_code().as_u16(), message),
+        };
+        mapped.with_source(err)
+    }
+}
+
+/// `JsonConfig` error handler that responds with an envelope.
+pub fn json_error_handler(err: JsonPayloadError, _: &HttpRequest) -> actix_web::Error {
+    Error::from(err).into()
+}
+
+/// `PathConfig` error handler that responds with an envelope.
+pub fn path_error_handler(err: PathError, _: &HttpRequest) -> actix_web::Error {
+    Error::from(err).into()
+}
+
+/// `QueryConfig` error handler that responds with an envelope.
+pub fn query_error_handler(err: QueryPayloadError, _: &HttpRequest) -> actix_web::Error {
+    Error::from(err).into()
+}
+
+/// `FormConfig` error handler that responds with an envelope.
+pub fn form_error_handler(err: UrlencodedError, _: &HttpRequest) -> actix_web::Error {
+    Error::from(err).into()
+}
+
+/// Installs the envelope error handlers for the JSON, path, query and form
+/// extractors.
+///
+/// Use with `App::configure`. Apps that need custom extractor limits can
+/// set the handlers on their own configs instead.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(json_error_handler))
+        .app_data(web::PathConfig::default().error_handler(path_error_handler))
+        .app_data(web::QueryConfig::default().error_handler(query_error_handler))
+        .app_data(web::FormConfig::default().error_handler(form_error_handler));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{body, test as actix_test, App};
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    #[derive(Deserialize)]
+    struct Page {
+        #[allow(dead_code)]
+        page: u32,
+    }
+
+    async fn failing() -> Result<String, Error> {
+        Err(Error::unavailable("maintenance")
+            .with_trace_id("abc-123")
+            .with_retry_after(Duration::from_secs(90)))
+    }
+
+    async fn paged(_: web::Query<Page>) -> &'static str {
+        "ok"
+    }
+
+    #[actix_web::test]
+    async fn test_matches_http_response() {
+        let app = actix_test::init_service(App::new().route("/", web::get().to(failing))).await;
+        let response =
+            actix_test::call_service(&app, actix_test::TestRequest::get().to_request()).await;
+        let expected = failing().await.unwrap_err().into_http_response();
+
+        assert_eq!(response.status().as_u16(), expected.status().as_u16());
+        for name in ["content-type", "retry-after", "x-request-id"] {
+            assert_eq!(
+                response.headers().get(name).unwrap().as_bytes(),
+                expected.headers()[name].as_bytes()
+            );
+        }
+        let body = body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), expected.body().as_slice());
+    }
+
+    #[actix_web::test]
+    async fn test_extractor_errors() {
+        let app = actix_test::init_service(
+            App::new()
+                .configure(configure)
+                .route("/", web::get().to(paged)),
+        )
+        .await;
+        let request = actix_test::TestRequest::get().uri("/").to_request();
+        let response = actix_test::call_service(&app, request).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let json: serde_json::Value = actix_test::read_body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert_eq!(json["details"]["fields"]["page"], "missing field `page`");
+    }
+
+    #[test]
+    fn test_json_content_type() {
+        let err = Error::from(JsonPayloadError::ContentType);
+        assert_eq!(err.status, 415);
+    }
+}
@@ -15,8 +15,12 @@ use crate::{Code, Error};
 /// ```
 impl From<anyhow::Error> for Error {
     fn from(err: anyhow::Error) -> Self {
-        // Convert anyhow::Error to internal error with the error message
-        Error::new(Code::Internal, 500, err.to_string()).with_retryable(false)
+        // Convert anyhow::Error to internal error with the error message,
+        // keeping the anyhow error as the source so its chain stays walkable
+        let message = err.to_string();
+        Error::new(Code::Internal, 500, message)
+            .with_boxed_cause(err.into())
+            .with_retryable(false)
     }
 }
 
@@ -32,7 +36,7 @@ mod tests {
         assert_eq!(env_err.code, Code::Internal);
         assert_eq!(env_err.status(), 500);
         assert_eq!(env_err.message, "something went wrong");
-        assert_eq!(env_err.retryable, false);
+        assert!(!env_err.retryable);
     }
 
     #[test]
@@ -51,6 +55,7 @@ mod tests {
             Err(anyhow::anyhow!("test error"))
         }
 
+        #[allow(clippy::result_large_err)]
         fn handler() -> Result<String, Error> {
             let result = anyhow_function()?;
             Ok(result)
@@ -61,4 +66,17 @@ mod tests {
         let err = result.unwrap_err();
         assert_eq!(err.message, "test error");
     }
+
+    #[test]
+    fn preserves_anyhow_chain() {
+        use std::error::Error as _;
+
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "config.toml");
+        let anyhow_err = anyhow::Error::new(io_err).context("failed to load config");
+        let env_err: Error = anyhow_err.into();
+
+        assert!(env_err.source().is_some());
+        let io_err = env_err.find_cause::<std::io::Error>().unwrap();
+        assert_eq!(io_err.kind(), std::io::ErrorKind::NotFound);
+    }
 }
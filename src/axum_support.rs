@@ -23,47 +23,600 @@
 //! }
 //! ```
 
-use crate::Error;
+use crate::rejection::{field_error, named_field, status_error};
+use crate::{Error, Format, Redaction};
 use axum::{
-    http::StatusCode,
+    body::Body,
+    extract::{
+        path::ErrorKind as PathErrorKind,
+        rejection::{FormRejection, JsonRejection, PathRejection, QueryRejection},
+        FromRequest, FromRequestParts, RawPathParams, Request,
+    },
+    http::{header, request::Parts, HeaderValue, Method, Uri},
     response::{IntoResponse, Response},
-    Json,
+    Router,
 };
+use serde::{de::DeserializeOwned, Serialize};
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+// Re-exported so axum users find the trace ID layer next to `CatchPanicLayer`
+pub use crate::tower_support::{current_trace_id, TraceId, TraceIdLayer, TraceIdService};
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        // The original error rides along in the response extensions
+        self.into_http_response_with(Body::from)
+    }
+}
+
+impl From<JsonRejection> for Error {
+    fn from(rejection: JsonRejection) -> Self {
+        let message = rejection.body_text();
+        let err = match &rejection {
+            JsonRejection::JsonDataError(_) => match json_field(&rejection) {
+                Some((path, reason)) => field_error(path, reason, message),
+                None => Error::validation(message),
+            },
+            JsonRejection::JsonSyntaxError(_) => Error::bad_request(message),
+            _ => status_error(rejection.status().as_u16(), message),
+        };
+        err.with_source(rejection)
+    }
+}
+
+impl From<PathRejection> for Error {
+    fn from(rejection: PathRejection) -> Self {
+        path_error(rejection, &[])
+    }
+}
+
+/// Converts a path rejection, naming parameters that failed by position
+/// (tuple `Path`s) or without a key (single-value `Path`s) by the route
+/// key in `keys` when one is known.
+fn path_error(rejection: PathRejection, keys: &[String]) -> Error {
+    let message = rejection.body_text();
+    let field = match &rejection {
+        PathRejection::FailedToDeserializePathParams(inner) => match inner.kind() {
+            PathErrorKind::ParseErrorAtKey { key, .. }
+            | PathErrorKind::InvalidUtf8InPathParam { key } => {
+                Some((key.clone(), inner.kind().to_string()))
+            }
+            PathErrorKind::ParseErrorAtIndex { index, .. } => {
+                let key = keys.get(*index).cloned();
+                Some((
+                    key.unwrap_or_else(|| index.to_string()),
+                    inner.kind().to_string(),
+                ))
+            }
+            // A single value like `Path<u64>` reports no key of its own
+            PathErrorKind::ParseError { .. } if keys.len() == 1 => {
+                Some((keys[0].clone(), inner.kind().to_string()))
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    let err = match field {
+        Some((field, reason)) => field_error(field, reason, message),
+        None => status_error(rejection.status().as_u16(), message),
+    };
+    err.with_source(rejection)
+}
 
-        // Clone fields we need before moving self into JSON
-        let retry_after = self.retry_after;
-        let trace_id = self.trace_id.clone();
+impl From<QueryRejection> for Error {
+    fn from(rejection: QueryRejection) -> Self {
+        let message = rejection.body_text();
+        let err = match named_field(&message) {
+            Some(field) => field_error(field, reason(&rejection), message),
+            None => status_error(rejection.status().as_u16(), message),
+        };
+        err.with_source(rejection)
+    }
+}
 
-        // Create base response with JSON body
-        let mut response = (status, Json(self)).into_response();
+impl From<FormRejection> for Error {
+    fn from(rejection: FormRejection) -> Self {
+        let message = rejection.body_text();
+        let field = match &rejection {
+            FormRejection::FailedToDeserializeForm(_)
+            | FormRejection::FailedToDeserializeFormBody(_) => named_field(&message),
+            _ => None,
+        };
+        let err = match field {
+            Some(field) => field_error(field, reason(&rejection), message),
+            None => status_error(rejection.status().as_u16(), message),
+        };
+        err.with_source(rejection)
+    }
+}
 
-        // Add Retry-After header if specified
-        if let Some(duration) = retry_after {
-            let seconds = duration.as_secs().max(1);
-            response
-                .headers_mut()
-                .insert("Retry-After", seconds.to_string().parse().unwrap());
+/// Returns the underlying deserializer message of a rejection.
+fn reason(rejection: &(dyn std::error::Error + 'static)) -> String {
+    rejection
+        .source()
+        .map(|err| err.to_string())
+        .unwrap_or_else(|| rejection.to_string())
+}
+
+/// Returns the path and reason of a JSON data error, e.g. `("user.age", "...")`.
+fn json_field(rejection: &JsonRejection) -> Option<(String, String)> {
+    let mut current = std::error::Error::source(rejection);
+    while let Some(err) = current {
+        if let Some(err) = err.downcast_ref::<serde_path_to_error::Error<serde_json::Error>>() {
+            return error_field(err);
         }
+        current = err.source();
+    }
+    None
+}
+
+/// Returns the field path and reason of a deserialization error.
+///
+/// Errors at the top level (path `.`), such as a missing field, are named
+/// by the field in the message.
+fn error_field<E: fmt::Display>(err: &serde_path_to_error::Error<E>) -> Option<(String, String)> {
+    let reason = err.inner().to_string();
+    let path = err.path().to_string();
+    if path != "." {
+        return Some((path, reason));
+    }
+    named_field(&reason).map(|field| (field, reason))
+}
 
-        // Add X-Request-Id header if trace ID is present
-        if let Some(trace_id) = trace_id {
-            if let Ok(header_value) = trace_id.parse() {
-                response.headers_mut().insert("X-Request-Id", header_value);
+macro_rules! wrapper_extractor {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $name<T>(pub T);
+
+        impl<T> std::ops::Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
             }
         }
 
+        impl<T> std::ops::DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+    };
+}
+
+wrapper_extractor! {
+    /// `axum::Json` that rejects with an `Error` instead of plain text.
+    ///
+    /// Malformed JSON becomes `BAD_REQUEST`, a wrong content type
+    /// `UNSUPPORTED_MEDIA_TYPE`, an oversized body `PAYLOAD_TOO_LARGE`, and
+    /// a type mismatch `VALIDATION_FAILED` with the offending field path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use error_envelope::axum_support::Json;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct CreateUser {
+    ///     email: String,
+    /// }
+    ///
+    /// async fn create(Json(user): Json<CreateUser>) -> String {
+    ///     user.email
+    /// }
+    /// ```
+    Json
+}
+
+wrapper_extractor! {
+    /// `axum::extract::Path` that rejects with an `Error` naming the
+    /// parameter that failed to parse.
+    Path
+}
+
+wrapper_extractor! {
+    /// `axum::extract::Query` that rejects with an `Error` naming the query
+    /// parameter that failed to deserialize.
+    Query
+}
+
+wrapper_extractor! {
+    /// `axum::Form` that rejects with an `Error` instead of plain text.
+    Form
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Error> {
+        let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Json(value))
+    }
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for Form<T>
+where
+    axum::Form<T>: FromRequest<S, Rejection = FormRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Error> {
+        let axum::Form(value) = axum::Form::<T>::from_request(req, state).await?;
+        Ok(Form(value))
+    }
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    axum::extract::Path<T>: FromRequestParts<S, Rejection = PathRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        let rejection = match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => return Ok(Path(value)),
+            Err(rejection) => rejection,
+        };
+        let keys: Vec<String> = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map(|params| params.iter().map(|(key, _)| key.to_string()).collect())
+            .unwrap_or_default();
+        Err(path_error(rejection, &keys))
+    }
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Error> {
+        // Deserialized here rather than by axum to learn the failing field
+        let query = parts.uri.query().unwrap_or_default();
+        let deserializer =
+            serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+        serde_path_to_error::deserialize(deserializer)
+            .map(Query)
+            .map_err(|source| {
+                let message = format!("Failed to deserialize query string: {}", source.inner());
+                let err = match error_field(&source) {
+                    Some((field, reason)) => field_error(field, reason, message),
+                    None => Error::bad_request(message),
+                };
+                err.with_source(source)
+            })
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+impl<T: Serialize> IntoResponse for Form<T> {
+    fn into_response(self) -> Response {
+        axum::Form(self.0).into_response()
+    }
+}
+
+/// Fallback handler that responds with a `NOT_FOUND` envelope.
+///
+/// Install it with `Router::fallback` or [`RouterExt::envelope_fallbacks`].
+pub async fn not_found_fallback(uri: Uri) -> Error {
+    Error::not_found(format!("No route for {}", uri.path()))
+}
+
+/// Fallback handler that responds with a `METHOD_NOT_ALLOWED` envelope.
+///
+/// Install it with `Router::method_not_allowed_fallback` or
+/// [`RouterExt::envelope_fallbacks`]; Axum still adds the `Allow` header
+/// listing the permitted methods.
+pub async fn method_not_allowed_fallback(method: Method, uri: Uri) -> Error {
+    Error::method_not_allowed(format!("Method {} not allowed for {}", method, uri.path()))
+}
+
+/// Envelope helpers for `axum::Router`.
+pub trait RouterExt {
+    /// Responds to unmatched paths and methods with error envelopes.
+    ///
+    /// Call it after registering routes: the 405 fallback only applies to
+    /// routes that already exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use axum::{routing::get, Router};
+    /// use error_envelope::axum_support::RouterExt;
+    ///
+    /// let app: Router = Router::new()
+    ///     .route("/users", get(|| async { "users" }))
+    ///     .envelope_fallbacks();
+    /// ```
+    fn envelope_fallbacks(self) -> Self;
+}
+
+impl<S> RouterExt for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn envelope_fallbacks(self) -> Self {
+        self.fallback(not_found_fallback)
+            .method_not_allowed_fallback(method_not_allowed_fallback)
+    }
+}
+
+/// Per-router response configuration for error envelopes.
+///
+/// Re-renders any `Error` returned by the wrapped routes, overriding the
+/// process-wide defaults. Non-error responses pass through untouched.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum::{routing::get, Router};
+/// use error_envelope::{axum_support::EnvelopeLayer, Error, Format};
+///
+/// async fn handler() -> Result<String, Error> {
+///     Err(Error::not_found("User not found"))
+/// }
+///
+/// // Responds with application/problem+json for this router only
+/// let app: Router = Router::new()
+///     .route("/", get(handler))
+///     .layer(EnvelopeLayer::new().format(Format::Problem));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnvelopeLayer {
+    format: Option<Format>,
+    redaction: Option<Redaction>,
+}
+
+impl EnvelopeLayer {
+    /// Creates a layer that uses the process-wide defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the wire format for errors returned by the wrapped routes.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets the redaction policy for errors returned by the wrapped routes.
+    ///
+    /// The unredacted `Error` stays in the response extensions for logging.
+    pub fn redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = Some(redaction);
+        self
+    }
+}
+
+impl<S> Layer<S> for EnvelopeLayer {
+    type Service = EnvelopeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        EnvelopeService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`EnvelopeLayer`].
+#[derive(Debug, Clone)]
+pub struct EnvelopeService<S> {
+    inner: S,
+    layer: EnvelopeLayer,
+}
+
+impl<S> Service<Request> for EnvelopeService<S>
+where
+    S: Service<Request, Response = Response>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let instance = req.uri().path().to_string();
+        let layer = self.layer.clone();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+            Ok(layer.apply(response, &instance))
+        })
+    }
+}
+
+impl EnvelopeLayer {
+    fn apply(&self, mut response: Response, instance: &str) -> Response {
+        let Some(err) = response.extensions().get::<Error>() else {
+            return response;
+        };
+
+        let format = self.format.unwrap_or_else(crate::default_format);
+        let redaction = self.redaction.unwrap_or_else(crate::default_redaction);
+        let instance = match format {
+            Format::Problem => Some(instance),
+            Format::Envelope => None,
+        };
+        let body = err.render_body(format, redaction, instance);
+
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(format.content_type()),
+        );
+        headers.remove(header::CONTENT_LENGTH);
+        *response.body_mut() = Body::from(body);
         response
     }
 }
 
+type PanicHook = Arc<dyn Fn(&str, &Error) + Send + Sync>;
+
+/// Converts panics in the wrapped routes into `INTERNAL` envelopes.
+///
+/// The client only sees the redacted error (code, default message and trace
+/// ID); the panic message goes to the [`on_panic`](Self::on_panic) hook
+/// together with the unredacted `Error`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum::{routing::get, Router};
+/// use error_envelope::axum_support::{CatchPanicLayer, TraceIdLayer};
+///
+/// async fn handler() -> String {
+///     panic!("unexpected state")
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/", get(handler))
+///     .layer(CatchPanicLayer::new().on_panic(|message, err| {
+///         eprintln!("[{:?}] handler panicked: {}", err.trace_id, message);
+///     }))
+///     .layer(TraceIdLayer::new());
+/// ```
+#[derive(Clone, Default)]
+pub struct CatchPanicLayer {
+    hook: Option<PanicHook>,
+}
+
+impl CatchPanicLayer {
+    /// Creates a layer without a panic hook.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the hook called with the panic message and the unredacted error.
+    pub fn on_panic<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &Error) + Send + Sync + 'static,
+    {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    fn recover(&self, payload: Box<dyn Any + Send>) -> Response {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.as_str()
+        } else {
+            "non-string panic payload"
+        };
+
+        let mut err = Error::internal(format!("handler panicked: {}", message));
+        err.trace_id = current_trace_id();
+        if let Some(hook) = &self.hook {
+            hook(message, &err);
+        }
+        err.redacted().into_response()
+    }
+}
+
+impl fmt::Debug for CatchPanicLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CatchPanicLayer")
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
+
+impl<S> Layer<S> for CatchPanicLayer {
+    type Service = CatchPanicService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CatchPanicService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`CatchPanicLayer`].
+#[derive(Debug, Clone)]
+pub struct CatchPanicService<S> {
+    inner: S,
+    layer: CatchPanicLayer,
+}
+
+impl<S> Service<Request> for CatchPanicService<S>
+where
+    S: Service<Request, Response = Response>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let layer = self.layer.clone();
+        let future = match panic::catch_unwind(AssertUnwindSafe(|| self.inner.call(req))) {
+            Ok(future) => future,
+            // Recover inside the future so the request's trace ID is in scope
+            Err(payload) => return Box::pin(async move { Ok(layer.recover(payload)) }),
+        };
+
+        Box::pin(async move {
+            let mut future = Box::pin(future);
+            let caught = std::future::poll_fn(|cx| {
+                match panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+                    Ok(Poll::Ready(result)) => Poll::Ready(Ok(result)),
+                    Ok(Poll::Pending) => Poll::Pending,
+                    Err(payload) => Poll::Ready(Err(payload)),
+                }
+            })
+            .await;
+
+            match caught {
+                Ok(result) => result,
+                Err(payload) => Ok(layer.recover(payload)),
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Code;
+    use axum::http::StatusCode;
     use std::time::Duration;
 
     #[tokio::test]
@@ -90,4 +643,391 @@ mod tests {
 
         assert!(response.headers().contains_key("X-Request-Id"));
     }
+
+    #[tokio::test]
+    async fn test_matches_http_response() {
+        let err = Error::unavailable("maintenance")
+            .with_trace_id("abc-123")
+            .with_retry_after(Duration::from_secs(90));
+        let expected = err.clone().into_http_response();
+        let response = err.into_response();
+
+        assert_eq!(response.status(), expected.status());
+        assert_eq!(response.headers(), expected.headers());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.as_ref(), expected.body().as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_envelope_layer_problem_format() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        async fn handler() -> Result<String, Error> {
+            Err(Error::not_found("user not found").with_trace_id("abc-123"))
+        }
+
+        let app = Router::new()
+            .route("/users/:id", get(handler))
+            .layer(EnvelopeLayer::new().format(Format::Problem));
+
+        let request = Request::builder()
+            .uri("/users/42")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/problem+json"
+        );
+        assert_eq!(response.headers()["x-request-id"], "abc-123");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem["type"], "about:blank");
+        assert_eq!(problem["status"], 404);
+        assert_eq!(problem["detail"], "user not found");
+        assert_eq!(problem["instance"], "/users/42");
+        assert_eq!(problem["code"], "NOT_FOUND");
+        assert_eq!(problem["trace_id"], "abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_envelope_layer_ignores_success() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(EnvelopeLayer::new().format(Format::Problem));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+    }
+
+    #[tokio::test]
+    async fn test_envelope_layer_redaction() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        async fn handler() -> Result<String, Error> {
+            Err(
+                Error::internal("db: password authentication failed for user admin")
+                    .with_details(serde_json::json!({"host": "10.0.0.5"}))
+                    .with_trace_id("abc-123"),
+            )
+        }
+
+        let app = Router::new()
+            .route("/", get(handler))
+            .layer(EnvelopeLayer::new().redaction(Redaction::ServerErrors));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        // Original is retained for logging
+        let original = response.extensions().get::<Error>().unwrap();
+        assert!(original.message.contains("password authentication"));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "INTERNAL");
+        assert_eq!(json["message"], "Internal error");
+        assert_eq!(json["trace_id"], "abc-123");
+        assert!(json.get("details").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_trace_id_layer_fills_errors() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        async fn handler(axum::Extension(trace_id): axum::Extension<TraceId>) -> Error {
+            assert_eq!(current_trace_id().as_deref(), Some(trace_id.as_str()));
+            Error::not_found("user not found")
+        }
+
+        let app = Router::new()
+            .route("/", get(handler))
+            .layer(TraceIdLayer::new());
+
+        let request = Request::builder()
+            .uri("/")
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .header("x-request-id", "ignored")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(
+            response.headers()["x-request-id"],
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_json_rejections() {
+        use axum::{extract::DefaultBodyLimit, routing::post, Router};
+        use tower::ServiceExt;
+
+        #[derive(serde::Deserialize)]
+        struct User {
+            #[allow(dead_code)]
+            profile: Profile,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Profile {
+            #[allow(dead_code)]
+            age: u32,
+        }
+
+        async fn handler(Json(_): Json<User>) {}
+
+        let app = Router::new()
+            .route("/", post(handler))
+            .layer(DefaultBodyLimit::max(64));
+        let send = |content_type: &'static str, body: &'static str| {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", content_type)
+                .body(Body::from(body))
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        let response = send("application/json", r#"{"profile": {"age": "x"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert!(json["details"]["fields"]["profile.age"]
+            .as_str()
+            .unwrap()
+            .contains("invalid type"));
+
+        // Missing top-level fields are named from the message
+        let response = send("application/json", r#"{"age": 1}"#).await.unwrap();
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert!(json["details"]["fields"]["profile"]
+            .as_str()
+            .unwrap()
+            .starts_with("missing field `profile`"));
+
+        let response = send("application/json", "{").await.unwrap();
+        assert_eq!(body_json(response).await["code"], "BAD_REQUEST");
+
+        let response = send("text/plain", "{}").await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(body_json(response).await["code"], "UNSUPPORTED_MEDIA_TYPE");
+
+        let large = "{\"profile\": {\"age\": 1}}                                               ";
+        let response = send("application/json", large).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body_json(response).await["code"], "PAYLOAD_TOO_LARGE");
+    }
+
+    #[tokio::test]
+    async fn test_path_and_query_rejections() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        #[derive(serde::Deserialize)]
+        struct Page {
+            #[allow(dead_code)]
+            page: u32,
+        }
+
+        async fn handler(Path(_): Path<u64>, Query(_): Query<Page>) {}
+
+        let app = Router::new().route("/users/:id", get(handler));
+
+        // A single-value Path is named by its only route key
+        let request = Request::builder()
+            .uri("/users/abc?page=1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert!(json["details"]["fields"]["id"]
+            .as_str()
+            .unwrap()
+            .contains("abc"));
+
+        let request = Request::builder()
+            .uri("/users/42?page=abc")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert_eq!(
+            json["details"]["fields"]["page"],
+            "invalid digit found in string"
+        );
+
+        let request = Request::builder()
+            .uri("/users/42")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert_eq!(json["details"]["fields"]["page"], "missing field `page`");
+    }
+
+    #[tokio::test]
+    async fn test_path_field_rejections() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        #[derive(serde::Deserialize)]
+        struct Params {
+            #[allow(dead_code)]
+            id: u64,
+        }
+
+        async fn by_key(Path(_): Path<Params>) {}
+        async fn by_index(Path(_): Path<(u64, u32)>) {}
+
+        let app = Router::new()
+            .route("/users/:id", get(by_key))
+            .route("/orders/:order_id/items/:item_id", get(by_index));
+
+        let request = Request::builder()
+            .uri("/users/abc")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert!(json["details"]["fields"]["id"]
+            .as_str()
+            .unwrap()
+            .contains("abc"));
+
+        let request = Request::builder()
+            .uri("/orders/7/items/x")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert!(json["details"]["fields"]["item_id"]
+            .as_str()
+            .unwrap()
+            .contains("index 1"));
+    }
+
+    #[tokio::test]
+    async fn test_envelope_fallbacks() {
+        use axum::routing::get;
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/users",
+                get(|| async { "users" }).post(|| async { "created" }),
+            )
+            .envelope_fallbacks();
+
+        let request = Request::builder()
+            .uri("/missing")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "NOT_FOUND");
+        assert_eq!(json["message"], "No route for /missing");
+
+        let request = Request::builder()
+            .method("DELETE")
+            .uri("/users")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()["allow"], "GET,HEAD,POST");
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "METHOD_NOT_ALLOWED");
+        assert_eq!(json["message"], "Method DELETE not allowed for /users");
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic_layer() {
+        use axum::routing::get;
+        use std::sync::Mutex;
+        use tower::ServiceExt;
+
+        async fn handler() -> String {
+            panic!("db pool poisoned")
+        }
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let hook_reported = reported.clone();
+        let app = Router::new()
+            .route("/", get(handler))
+            .layer(CatchPanicLayer::new().on_panic(move |message, err| {
+                hook_reported
+                    .lock()
+                    .unwrap()
+                    .push((message.to_string(), err.trace_id.clone()));
+            }))
+            .layer(TraceIdLayer::new());
+
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "abc-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let json = body_json(response).await;
+        assert_eq!(json["code"], "INTERNAL");
+        assert_eq!(json["message"], "Internal error");
+        assert_eq!(json["trace_id"], "abc-123");
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(
+            *reported,
+            vec![("db pool poisoned".to_string(), Some("abc-123".to_string()))]
+        );
+    }
 }
@@ -0,0 +1,244 @@
+use crate::{Code, Error};
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, RwLock};
+
+type Rule = Box<dyn Fn(&(dyn StdError + 'static)) -> Option<Error> + Send + Sync>;
+
+/// Ordered set of rules that map arbitrary errors into an `Error`.
+///
+/// `classify` consults the custom rules in registration order, then the
+/// built-in downcasts used by [`from`](crate::from), then message
+/// heuristics, and finally the fallback code. Each rule is tried against
+/// the error and every error in its source chain.
+///
+/// # Example
+/// ```
+/// use error_envelope::{set_classifier, Classifier, Code, Error};
+///
+/// #[derive(Debug)]
+/// struct QuotaExceeded;
+/// impl std::fmt::Display for QuotaExceeded {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "quota exceeded")
+///     }
+/// }
+/// impl std::error::Error for QuotaExceeded {}
+///
+/// let classifier = Classifier::new().map::<QuotaExceeded>(Code::RateLimited);
+/// assert_eq!(classifier.classify(QuotaExceeded).code, Code::RateLimited);
+///
+/// // Or make it the classifier used by error_envelope::from
+/// set_classifier(classifier);
+/// ```
+pub struct Classifier {
+    rules: Vec<Rule>,
+    heuristics: bool,
+    fallback: Code,
+}
+
+impl Classifier {
+    /// Creates a classifier with the built-in rules and an Internal fallback.
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            heuristics: true,
+            fallback: Code::Internal,
+        }
+    }
+
+    /// Adds a rule for errors of type `E`.
+    ///
+    /// Returning `None` passes the error on to the next rule. The original
+    /// error is attached as the cause unless the rule set one itself.
+    pub fn rule<E, F>(mut self, rule: F) -> Self
+    where
+        E: StdError + 'static,
+        F: Fn(&E) -> Option<Error> + Send + Sync + 'static,
+    {
+        self.rules
+            .push(Box::new(move |err| err.downcast_ref::<E>().and_then(&rule)));
+        self
+    }
+
+    /// Maps every error of type `E` to `code`.
+    pub fn map<E>(self, code: Code) -> Self
+    where
+        E: StdError + 'static,
+    {
+        self.rule(move |_: &E| Some(Error::new(code, 0, "")))
+    }
+
+    /// Enables or disables the message heuristics for unknown error types.
+    pub fn heuristics(mut self, enabled: bool) -> Self {
+        self.heuristics = enabled;
+        self
+    }
+
+    /// Sets the code used when nothing matches (Internal by default).
+    pub fn fallback(mut self, code: Code) -> Self {
+        self.fallback = code;
+        self
+    }
+
+    /// Maps an error into an `Error`, keeping it as the source.
+    pub fn classify(&self, err: impl StdError + Send + Sync + 'static) -> Error {
+        match self.matching_rule(&err) {
+            Some(mapped) if mapped.cause().is_some() => mapped,
+            Some(mapped) => mapped.with_source(err),
+            None => Error::new(self.unmatched_code(&err), 0, "").with_source(err),
+        }
+    }
+
+    /// Like [`classify`](Classifier::classify), but keeps only the cause
+    /// message.
+    pub(crate) fn classify_message(&self, err: &(dyn StdError + 'static)) -> Error {
+        match self.matching_rule(err) {
+            Some(mapped) if mapped.cause().is_some() => mapped,
+            Some(mapped) => mapped.with_cause_message(err),
+            None => Error::new(self.unmatched_code(err), 0, "").with_cause_message(err),
+        }
+    }
+
+    /// Returns the first custom rule's mapping for the error or its sources.
+    fn matching_rule(&self, err: &(dyn StdError + 'static)) -> Option<Error> {
+        self.rules.iter().find_map(|rule| {
+            let mut current = Some(err);
+            while let Some(inner) = current {
+                if let Some(mapped) = rule(inner) {
+                    return Some(mapped);
+                }
+                current = inner.source();
+            }
+            None
+        })
+    }
+
+    /// Picks a code from the built-in downcasts, heuristics or fallback.
+    fn unmatched_code(&self, err: &(dyn StdError + 'static)) -> Code {
+        known_code(err)
+            .or_else(|| {
+                self.heuristics
+                    .then(|| heuristic_code(&err.to_string()))
+                    .flatten()
+            })
+            .unwrap_or(self.fallback)
+    }
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Classifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Classifier")
+            .field("rules", &self.rules.len())
+            .field("heuristics", &self.heuristics)
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+static GLOBAL: RwLock<Option<Arc<Classifier>>> = RwLock::new(None);
+
+/// Installs the process-wide classifier used by [`from`](crate::from).
+pub fn set_classifier(classifier: Classifier) {
+    let mut global = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+    *global = Some(Arc::new(classifier));
+}
+
+/// Classifies with the process-wide classifier (or the default one).
+pub(crate) fn classify_global(err: &(dyn StdError + 'static)) -> Error {
+    let installed = GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match installed {
+        Some(classifier) => classifier.classify_message(err),
+        None => Classifier::new().classify_message(err),
+    }
+}
+
+/// Classifies well-known error types by downcasting, walking the source
+/// chain until something matches.
+pub(crate) fn known_code(err: &(dyn StdError + 'static)) -> Option<Code> {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if let Some(code) = downcast_code(err) {
+            return Some(code);
+        }
+        current = err.source();
+    }
+    None
+}
+
+fn downcast_code(err: &(dyn StdError + 'static)) -> Option<Code> {
+    if let Some(err) = err.downcast_ref::<io::Error>() {
+        return io_code(err.kind());
+    }
+
+    if let Some(err) = err.downcast_ref::<serde_json::Error>() {
+        return Some(match err.classify() {
+            serde_json::error::Category::Io => Code::Internal,
+            _ => Code::BadRequest,
+        });
+    }
+
+    #[cfg(feature = "tokio-support")]
+    if err.is::<tokio::time::error::Elapsed>() {
+        return Some(Code::Timeout);
+    }
+
+    None
+}
+
+fn io_code(kind: io::ErrorKind) -> Option<Code> {
+    use io::ErrorKind;
+
+    let code = match kind {
+        ErrorKind::TimedOut => Code::Timeout,
+        ErrorKind::NotFound => Code::NotFound,
+        ErrorKind::PermissionDenied => Code::Forbidden,
+        ErrorKind::AlreadyExists => Code::Conflict,
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => Code::BadRequest,
+        ErrorKind::ConnectionRefused
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted
+        | ErrorKind::NotConnected
+        | ErrorKind::BrokenPipe => Code::Unavailable,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Phrases that indicate a timeout when no typed match was found.
+const TIMEOUT_PHRASES: &[&str] = &["timed out", "timeout", "deadline exceeded"];
+
+/// Phrases that indicate cancellation when no typed match was found.
+///
+/// Bare "cancel" is deliberately absent: messages like "user cancelled
+/// subscription" describe domain state, not a canceled request.
+const CANCEL_PHRASES: &[&str] = &[
+    "operation canceled",
+    "operation cancelled",
+    "request canceled",
+    "request cancelled",
+    "context canceled",
+    "task was cancelled",
+];
+
+/// Falls back to message heuristics for errors of unknown types.
+pub(crate) fn heuristic_code(message: &str) -> Option<Code> {
+    let message = message.to_lowercase();
+    if TIMEOUT_PHRASES
+        .iter()
+        .any(|phrase| message.contains(phrase))
+    {
+        return Some(Code::Timeout);
+    }
+    if CANCEL_PHRASES.iter().any(|phrase| message.contains(phrase)) {
+        return Some(Code::Canceled);
+    }
+    None
+}
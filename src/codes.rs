@@ -1,8 +1,10 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
 
 /// Machine-readable error codes that remain stable across releases.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Code {
     /// Internal server error (500).
     Internal,
@@ -18,6 +20,8 @@ pub enum Code {
     Conflict,
     /// Request payload exceeds size limit (413).
     PayloadTooLarge,
+    /// Request content type is not supported (415).
+    UnsupportedMediaType,
     /// Request timed out before completion (408).
     RequestTimeout,
     /// Too many requests, client should retry later (429).
@@ -43,9 +47,35 @@ pub enum Code {
     DownstreamError,
     /// Downstream service timed out (504).
     DownstreamTimeout,
+
+    /// Application-defined code (see [`CustomCode`]).
+    Custom(CustomCode),
 }
 
 impl Code {
+    /// All built-in codes, in declaration order.
+    pub const BUILTIN: [Code; 19] = [
+        Code::Internal,
+        Code::BadRequest,
+        Code::NotFound,
+        Code::MethodNotAllowed,
+        Code::Gone,
+        Code::Conflict,
+        Code::PayloadTooLarge,
+        Code::UnsupportedMediaType,
+        Code::RequestTimeout,
+        Code::RateLimited,
+        Code::Unavailable,
+        Code::ValidationFailed,
+        Code::Unauthorized,
+        Code::Forbidden,
+        Code::UnprocessableEntity,
+        Code::Timeout,
+        Code::Canceled,
+        Code::DownstreamError,
+        Code::DownstreamTimeout,
+    ];
+
     /// Returns the default HTTP status code for this error code.
     pub fn default_status(&self) -> u16 {
         match self {
@@ -56,6 +86,7 @@ impl Code {
             Code::Gone => 410,
             Code::Conflict => 409,
             Code::PayloadTooLarge => 413,
+            Code::UnsupportedMediaType => 415,
             Code::RequestTimeout => 408,
             Code::RateLimited => 429,
             Code::Unavailable => 503,
@@ -67,19 +98,57 @@ impl Code {
             Code::Canceled => 499,
             Code::DownstreamError => 502,
             Code::DownstreamTimeout => 504,
+            Code::Custom(custom) => custom.status,
+        }
+    }
+
+    /// Returns the built-in code that best describes an HTTP status.
+    ///
+    /// Used when a response carries no envelope. Unlisted 4xx statuses map
+    /// to `BadRequest` and anything else to `Internal`.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::Code;
+    ///
+    /// assert_eq!(Code::from_status(404), Code::NotFound);
+    /// assert_eq!(Code::from_status(502), Code::DownstreamError);
+    /// ```
+    pub fn from_status(status: u16) -> Code {
+        match status {
+            401 => Code::Unauthorized,
+            403 => Code::Forbidden,
+            404 => Code::NotFound,
+            405 => Code::MethodNotAllowed,
+            408 => Code::RequestTimeout,
+            409 => Code::Conflict,
+            410 => Code::Gone,
+            413 => Code::PayloadTooLarge,
+            415 => Code::UnsupportedMediaType,
+            422 => Code::UnprocessableEntity,
+            429 => Code::RateLimited,
+            499 => Code::Canceled,
+            502 => Code::DownstreamError,
+            503 => Code::Unavailable,
+            504 => Code::Timeout,
+            400..=499 => Code::BadRequest,
+            _ => Code::Internal,
         }
     }
 
     /// Returns whether this error is retryable by default.
     pub fn is_retryable_default(&self) -> bool {
-        matches!(
-            self,
-            Code::Timeout
-                | Code::DownstreamTimeout
-                | Code::Unavailable
-                | Code::RateLimited
-                | Code::RequestTimeout
-        )
+        match self {
+            Code::Custom(custom) => custom.retryable,
+            _ => matches!(
+                self,
+                Code::Timeout
+                    | Code::DownstreamTimeout
+                    | Code::Unavailable
+                    | Code::RateLimited
+                    | Code::RequestTimeout
+            ),
+        }
     }
 
     /// Returns a default human-readable message for this code.
@@ -94,6 +163,7 @@ impl Code {
             Code::Gone => "Resource no longer exists",
             Code::Conflict => "Conflict",
             Code::PayloadTooLarge => "Payload too large",
+            Code::UnsupportedMediaType => "Unsupported media type",
             Code::UnprocessableEntity => "Unprocessable entity",
             Code::RateLimited => "Rate limited",
             Code::RequestTimeout | Code::Timeout | Code::DownstreamTimeout => "Request timed out",
@@ -101,6 +171,213 @@ impl Code {
             Code::Canceled => "Request canceled",
             Code::DownstreamError => "Downstream service error",
             Code::MethodNotAllowed => "Method not allowed",
+            Code::Custom(custom) => custom.message,
         }
     }
+
+    /// Returns the wire name of this code (e.g. `NOT_FOUND`).
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Code::Internal => "INTERNAL",
+            Code::BadRequest => "BAD_REQUEST",
+            Code::NotFound => "NOT_FOUND",
+            Code::MethodNotAllowed => "METHOD_NOT_ALLOWED",
+            Code::Gone => "GONE",
+            Code::Conflict => "CONFLICT",
+            Code::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            Code::UnsupportedMediaType => "UNSUPPORTED_MEDIA_TYPE",
+            Code::RequestTimeout => "REQUEST_TIMEOUT",
+            Code::RateLimited => "RATE_LIMITED",
+            Code::Unavailable => "UNAVAILABLE",
+            Code::ValidationFailed => "VALIDATION_FAILED",
+            Code::Unauthorized => "UNAUTHORIZED",
+            Code::Forbidden => "FORBIDDEN",
+            Code::UnprocessableEntity => "UNPROCESSABLE_ENTITY",
+            Code::Timeout => "TIMEOUT",
+            Code::Canceled => "CANCELED",
+            Code::DownstreamError => "DOWNSTREAM_ERROR",
+            Code::DownstreamTimeout => "DOWNSTREAM_TIMEOUT",
+            Code::Custom(custom) => custom.name,
+        }
+    }
+
+    /// Looks up a code by its wire name.
+    ///
+    /// Built-in codes are matched first, then codes added with
+    /// [`CustomCode::register`].
+    pub fn from_name(name: &str) -> Option<Code> {
+        if let Some(code) = Code::BUILTIN.iter().find(|code| code.as_str() == name) {
+            return Some(*code);
+        }
+
+        let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+        registry
+            .iter()
+            .find(|custom| custom.name == name)
+            .map(|custom| Code::Custom(*custom))
+    }
+}
+
+impl From<CustomCode> for Code {
+    fn from(custom: CustomCode) -> Self {
+        Code::Custom(custom)
+    }
+}
+
+impl Serialize for Code {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Code {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        Code::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown error code `{}`", name)))
+    }
+}
+
+static REGISTRY: RwLock<Vec<CustomCode>> = RwLock::new(Vec::new());
+
+/// Application-defined error code.
+///
+/// Custom codes serialize as their own SCREAMING_SNAKE_CASE name and carry
+/// their own default status, message and retryability.
+///
+/// # Example
+/// ```
+/// use error_envelope::{Code, CustomCode, Error};
+///
+/// const INSUFFICIENT_FUNDS: CustomCode =
+///     CustomCode::new("INSUFFICIENT_FUNDS", 402, "Insufficient funds");
+///
+/// let err = Error::new(INSUFFICIENT_FUNDS.into(), 0, "");
+/// assert_eq!(err.status, 402);
+/// assert_eq!(err.message, "Insufficient funds");
+/// assert!(error_envelope::is(&err, Code::Custom(INSUFFICIENT_FUNDS)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct CustomCode {
+    name: &'static str,
+    status: u16,
+    message: &'static str,
+    retryable: bool,
+}
+
+impl CustomCode {
+    /// Defines a custom code.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time in `const` context) if `name` is not
+    /// SCREAMING_SNAKE_CASE, `name` is a built-in code's name, or `status`
+    /// is not a 4xx/5xx status.
+    ///
+    /// ```compile_fail
+    /// use error_envelope::CustomCode;
+    ///
+    /// // Would deserialize back as Code::NotFound
+    /// const GONE_USER: CustomCode = CustomCode::new("NOT_FOUND", 410, "User gone");
+    /// ```
+    pub const fn new(name: &'static str, status: u16, message: &'static str) -> Self {
+        let bytes = name.as_bytes();
+        assert!(!bytes.is_empty(), "custom code name must not be empty");
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            assert!(
+                b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_',
+                "custom code name must be SCREAMING_SNAKE_CASE"
+            );
+            i += 1;
+        }
+        let mut j = 0;
+        while j < Code::BUILTIN.len() {
+            assert!(
+                !str_eq(name, Code::BUILTIN[j].as_str()),
+                "custom code name must not be a built-in code name"
+            );
+            j += 1;
+        }
+        assert!(
+            status >= 400 && status <= 599,
+            "custom code status must be 4xx or 5xx"
+        );
+
+        Self {
+            name,
+            status,
+            message,
+            retryable: false,
+        }
+    }
+
+    /// Sets whether errors with this code are retryable by default.
+    pub const fn with_retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Returns the wire name.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the default HTTP status.
+    pub const fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Registers the code so envelopes carrying it can be deserialized.
+    ///
+    /// Registering the same name twice replaces the earlier definition.
+    pub fn register(self) {
+        let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+        registry.retain(|custom| custom.name != self.name);
+        registry.push(self);
+    }
+}
+
+/// `str` equality usable in `const fn`.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// Custom codes are identified by name alone
+impl PartialEq for CustomCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomCode {}
+
+impl Hash for CustomCode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Debug for CustomCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
 }
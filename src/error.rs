@@ -1,8 +1,12 @@
 use crate::Code;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 
+/// Shared, cloneable handle to an underlying error.
+type Source = Arc<dyn std::error::Error + Send + Sync + 'static>;
+
 /// Structured error envelope for HTTP APIs.
 #[derive(Debug, Clone)]
 pub struct Error {
@@ -23,6 +27,7 @@ pub struct Error {
     pub retry_after: Option<Duration>,
 
     cause_message: Option<String>,
+    source: Option<Source>,
 }
 
 impl Error {
@@ -50,13 +55,15 @@ impl Error {
             status,
             retry_after: None,
             cause_message: None,
+            source: None,
         }
     }
 
     /// Creates a new error with a formatted message.
     ///
     /// This is a semantic alias for `new()` that signals the message
-    /// is typically constructed with `format!()`.
+    /// is typically constructed with `format!()`. The [`envelope!`](crate::envelope)
+    /// macro formats the message itself.
     ///
     /// # Example
     /// ```
@@ -69,15 +76,16 @@ impl Error {
     }
 
     /// Creates a new error that wraps an underlying cause.
+    ///
+    /// Only the cause message is kept; use [`with_source`](Error::with_source)
+    /// to keep the error itself.
     pub fn wrap(
         code: Code,
         status: u16,
         message: impl Into<String>,
         cause: impl std::error::Error,
     ) -> Self {
-        let mut err = Self::new(code, status, message);
-        err.cause_message = Some(cause.to_string());
-        err
+        Self::new(code, status, message).with_cause_message(cause)
     }
 
     /// Adds structured details to the error.
@@ -115,7 +123,8 @@ impl Error {
     /// Attaches a cause message from an underlying error.
     ///
     /// Useful when mapping domain errors (e.g., thiserror) to HTTP errors
-    /// while preserving the underlying error message for debugging.
+    /// while preserving the underlying error message for debugging. Use
+    /// [`with_source`](Error::with_source) to also keep the error itself.
     ///
     /// # Example
     /// ```
@@ -139,11 +148,69 @@ impl Error {
         self
     }
 
+    /// Attaches an underlying error as the source.
+    ///
+    /// Like [`with_cause_message`](Error::with_cause_message), but the error
+    /// itself is kept: it is returned from `std::error::Error::source` and
+    /// can be downcast with [`find_cause`](Error::find_cause).
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::Error;
+    /// use std::io;
+    ///
+    /// let cause = io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+    /// let err = Error::unavailable("database down").with_source(cause);
+    ///
+    /// assert_eq!(err.cause(), Some("refused"));
+    /// assert!(std::error::Error::source(&err).is_some());
+    /// ```
+    pub fn with_source(self, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        self.with_boxed_cause(Box::new(source))
+    }
+
+    /// Attaches an already boxed error as the cause.
+    pub(crate) fn with_boxed_cause(
+        mut self,
+        cause: Box<dyn std::error::Error + Send + Sync + 'static>,
+    ) -> Self {
+        self.cause_message = Some(cause.to_string());
+        self.source = Some(Arc::from(cause));
+        self
+    }
+
     /// Returns the cause message if available.
     pub fn cause(&self) -> Option<&str> {
         self.cause_message.as_deref()
     }
 
+    /// Searches the cause chain for an error of type `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::{Code, Error};
+    /// use std::io;
+    ///
+    /// let cause = io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+    /// let err = Error::new(Code::Unavailable, 503, "database down").with_source(cause);
+    ///
+    /// let io_err = err.find_cause::<io::Error>().unwrap();
+    /// assert_eq!(io_err.kind(), io::ErrorKind::ConnectionRefused);
+    /// ```
+    pub fn find_cause<T>(&self) -> Option<&T>
+    where
+        T: std::error::Error + 'static,
+    {
+        let mut current = std::error::Error::source(self);
+        while let Some(err) = current {
+            if let Some(found) = err.downcast_ref::<T>() {
+                return Some(found);
+            }
+            current = err.source();
+        }
+        None
+    }
+
     /// Returns the HTTP status code.
     pub fn status(&self) -> u16 {
         self.status
@@ -162,8 +229,9 @@ impl fmt::Display for Error {
 
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        // Since we only store the cause message, we can't return the original error
-        None
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
     }
 }
 
@@ -203,15 +271,118 @@ impl Serialize for Error {
         state.serialize_field("retryable", &self.retryable)?;
 
         if let Some(ref duration) = self.retry_after {
-            let secs = duration.as_secs();
-            let formatted = if secs < 60 {
-                format!("{}s", secs)
-            } else {
-                format!("{}m{}s", secs / 60, secs % 60)
-            };
-            state.serialize_field("retry_after", &formatted)?;
+            state.serialize_field("retry_after", &format_retry_after(*duration))?;
         }
 
         state.end()
     }
 }
+
+// Accepts everything `Serialize` writes, plus an optional `status` member;
+// unknown codes fall back to the status's code, or Internal without one
+impl<'de> Deserialize<'de> for Error {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RetryAfter {
+            Seconds(u64),
+            Text(String),
+        }
+
+        #[derive(Deserialize)]
+        struct Envelope {
+            code: String,
+            #[serde(default)]
+            message: String,
+            #[serde(default)]
+            details: Option<serde_json::Value>,
+            #[serde(default)]
+            trace_id: Option<String>,
+            #[serde(default)]
+            retryable: Option<bool>,
+            #[serde(default)]
+            retry_after: Option<RetryAfter>,
+            // Not written by `Serialize`; anything but a number is ignored
+            #[serde(default)]
+            status: Option<serde_json::Value>,
+        }
+
+        let envelope = Envelope::deserialize(deserializer)?;
+
+        let status = envelope
+            .status
+            .and_then(|status| status.as_u64())
+            .and_then(|status| u16::try_from(status).ok());
+        let code = parse_code(&envelope.code, status);
+        let mut err = Error::new(code, status.unwrap_or(0), envelope.message);
+        err.details = envelope.details;
+        err.trace_id = envelope.trace_id;
+        if let Some(retryable) = envelope.retryable {
+            err.retryable = retryable;
+        }
+
+        err.retry_after = match envelope.retry_after {
+            Some(RetryAfter::Seconds(secs)) => Some(Duration::from_secs(secs)),
+            Some(RetryAfter::Text(text)) => Some(parse_retry_after(&text).ok_or_else(|| {
+                serde::de::Error::custom(format!("invalid retry_after: {:?}", text))
+            })?),
+            None => None,
+        };
+
+        Ok(err)
+    }
+}
+
+/// Formats a retry-after duration as `30s` or `5m0s`.
+fn format_retry_after(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m{}s", secs / 60, secs % 60)
+    }
+}
+
+/// Parses durations like `30s`, `1m30s` or `2h` back into a `Duration`.
+fn parse_retry_after(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = text.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for ch in text.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let value: u64 = digits.parse().ok()?;
+        digits.clear();
+        let unit = match ch {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        total = total.checked_add(value.checked_mul(unit)?)?;
+    }
+
+    // Trailing digits without a unit are malformed
+    if !digits.is_empty() {
+        return None;
+    }
+    Some(Duration::from_secs(total))
+}
+
+/// Maps a wire code string to a `Code`, tolerating codes this version
+/// does not know about by falling back to the code for `status`.
+pub(crate) fn parse_code(name: &str, status: Option<u16>) -> Code {
+    Code::from_name(name).unwrap_or_else(|| status.map_or(Code::Internal, Code::from_status))
+}
@@ -0,0 +1,83 @@
+use crate::{Code, Error};
+
+/// Converts `Result` errors into envelopes, keeping the original error as
+/// the cause.
+///
+/// # Example
+/// ```
+/// use error_envelope::{Code, Error, ResultExt};
+///
+/// fn load_config() -> Result<String, Error> {
+///     std::fs::read_to_string("/missing/config.toml").or_internal("failed to load config")
+/// }
+///
+/// let err = load_config().unwrap_err();
+/// assert_eq!(err.code, Code::Internal);
+/// assert!(err.find_cause::<std::io::Error>().is_some());
+/// ```
+#[allow(clippy::result_large_err)]
+pub trait ResultExt<T, E> {
+    /// Maps the error to an `INTERNAL` (500) envelope.
+    fn or_internal(self, message: impl Into<String>) -> Result<T, Error>;
+
+    /// Maps the error to an envelope with the given code and its default
+    /// status.
+    fn or_code(self, code: Code, message: impl Into<String>) -> Result<T, Error>;
+
+    /// Maps the error with a closure; the original error is attached as the
+    /// cause of the returned envelope.
+    fn with_envelope<F>(self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&E) -> Error;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn or_internal(self, message: impl Into<String>) -> Result<T, Error> {
+        self.map_err(|err| Error::internal(message).with_source(err))
+    }
+
+    fn or_code(self, code: Code, message: impl Into<String>) -> Result<T, Error> {
+        self.map_err(|err| Error::new(code, 0, message).with_source(err))
+    }
+
+    fn with_envelope<F>(self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&E) -> Error,
+    {
+        self.map_err(|err| f(&err).with_source(err))
+    }
+}
+
+/// Converts `None` into an envelope.
+///
+/// # Example
+/// ```
+/// use error_envelope::{Code, OptionExt};
+///
+/// let users = vec!["ada"];
+/// let err = users.get(3).or_not_found("user").unwrap_err();
+///
+/// assert_eq!(err.code, Code::NotFound);
+/// assert_eq!(err.message, "user not found");
+/// ```
+#[allow(clippy::result_large_err)]
+pub trait OptionExt<T> {
+    /// Returns a `NOT_FOUND` (404) envelope reading "{resource} not found".
+    fn or_not_found(self, resource: impl std::fmt::Display) -> Result<T, Error>;
+
+    /// Returns an envelope with the given code and its default status.
+    fn or_code(self, code: Code, message: impl Into<String>) -> Result<T, Error>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn or_not_found(self, resource: impl std::fmt::Display) -> Result<T, Error> {
+        self.ok_or_else(|| Error::not_found(format!("{} not found", resource)))
+    }
+
+    fn or_code(self, code: Code, message: impl Into<String>) -> Result<T, Error> {
+        self.ok_or_else(|| Error::new(code, 0, message))
+    }
+}
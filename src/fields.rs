@@ -0,0 +1,346 @@
+use serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+/// Field-level validation errors.
+///
+/// Paths may be written dotted (`address.zip`, `items[2].qty`) or as RFC 6901
+/// JSON Pointers (`/items/2/qty`); both name the same field. A field can
+/// have any number of violations, each with an optional machine code and
+/// parameters.
+///
+/// Serialized (as [`validation`](crate::validation) details) into `fields`,
+/// mapping each path to its first message, and `violations`, the full list
+/// ordered by path, then by insertion. `fields` is a JSON object, so its key
+/// order is up to the serializer (`serde_json::Value` sorts keys as strings).
+///
+/// # Example
+/// ```
+/// use error_envelope::{validation, FieldErrors, Violation};
+///
+/// let mut errors = FieldErrors::new();
+/// errors.add("email", "required", "is required");
+/// errors.push(
+///     Violation::new("password", "must be at least 8 characters")
+///         .with_code("too_short")
+///         .with_param("min", 8),
+/// );
+/// errors.add("/items/2/qty", "range", "must be positive");
+///
+/// let details = validation(errors).details.unwrap();
+/// assert_eq!(details["fields"]["items[2].qty"], "must be positive");
+/// assert_eq!(details["violations"][1]["pointer"], "/items/2/qty");
+/// assert_eq!(details["violations"][2]["params"]["min"], 8);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldErrors {
+    violations: Vec<Violation>,
+}
+
+impl FieldErrors {
+    /// Creates an empty set of field errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message without a machine code.
+    pub fn insert(&mut self, path: impl AsRef<str>, message: impl Into<String>) -> &mut Self {
+        self.push(Violation::new(path, message))
+    }
+
+    /// Adds a message with a machine code such as `required` or `too_short`.
+    pub fn add(
+        &mut self,
+        path: impl AsRef<str>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> &mut Self {
+        self.push(Violation::new(path, message).with_code(code))
+    }
+
+    /// Adds a violation.
+    pub fn push(&mut self, violation: Violation) -> &mut Self {
+        self.violations.push(violation);
+        self
+    }
+
+    /// Adds the errors of a nested value under `prefix`.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::FieldErrors;
+    ///
+    /// let mut address = FieldErrors::new();
+    /// address.insert("zip", "is invalid");
+    ///
+    /// let mut errors = FieldErrors::new();
+    /// errors.nest("shipping.address", address);
+    /// assert_eq!(errors.get("shipping.address.zip"), Some("is invalid"));
+    /// ```
+    pub fn nest(&mut self, prefix: impl AsRef<str>, errors: FieldErrors) -> &mut Self {
+        let prefix = parse_path(prefix.as_ref());
+        for mut violation in errors.violations {
+            let mut segments = prefix.clone();
+            segments.append(&mut violation.segments);
+            violation.segments = segments;
+            self.violations.push(violation);
+        }
+        self
+    }
+
+    /// Returns the first message for a path, in either path syntax.
+    pub fn get(&self, path: impl AsRef<str>) -> Option<&str> {
+        let segments = parse_path(path.as_ref());
+        self.violations
+            .iter()
+            .find(|violation| violation.segments == segments)
+            .map(|violation| violation.message.as_str())
+    }
+
+    /// Returns the number of violations.
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    /// Returns whether there are no violations.
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Iterates over violations ordered by path, then by insertion.
+    pub fn iter(&self) -> impl Iterator<Item = &Violation> {
+        let mut sorted: Vec<&Violation> = self.violations.iter().collect();
+        sorted.sort_by(|a, b| a.segments.cmp(&b.segments));
+        sorted.into_iter()
+    }
+}
+
+impl Serialize for FieldErrors {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct Fields<'a>(&'a FieldErrors);
+
+        impl Serialize for Fields<'_> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut map = serializer.serialize_map(None)?;
+                let mut last: Option<&[Segment]> = None;
+                for violation in self.0.iter() {
+                    if last != Some(violation.segments.as_slice()) {
+                        map.serialize_entry(&violation.path(), &violation.message)?;
+                        last = Some(&violation.segments);
+                    }
+                }
+                map.end()
+            }
+        }
+
+        struct Violations<'a>(&'a FieldErrors);
+
+        impl Serialize for Violations<'_> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.collect_seq(self.0.iter())
+            }
+        }
+
+        let mut state = serializer.serialize_struct("FieldErrors", 2)?;
+        state.serialize_field("fields", &Fields(self))?;
+        state.serialize_field("violations", &Violations(self))?;
+        state.end()
+    }
+}
+
+impl From<HashMap<String, String>> for FieldErrors {
+    fn from(fields: HashMap<String, String>) -> Self {
+        fields
+            .into_iter()
+            .map(|(path, message)| Violation::new(path, message))
+            .collect()
+    }
+}
+
+impl From<BTreeMap<String, String>> for FieldErrors {
+    fn from(fields: BTreeMap<String, String>) -> Self {
+        fields
+            .into_iter()
+            .map(|(path, message)| Violation::new(path, message))
+            .collect()
+    }
+}
+
+impl FromIterator<Violation> for FieldErrors {
+    fn from_iter<I: IntoIterator<Item = Violation>>(iter: I) -> Self {
+        Self {
+            violations: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Violation> for FieldErrors {
+    fn extend<I: IntoIterator<Item = Violation>>(&mut self, iter: I) {
+        self.violations.extend(iter);
+    }
+}
+
+/// A single failed check on a field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    segments: Vec<Segment>,
+    code: Option<String>,
+    message: String,
+    params: Map<String, Value>,
+}
+
+impl Violation {
+    /// Creates a violation for a dotted path or JSON Pointer.
+    pub fn new(path: impl AsRef<str>, message: impl Into<String>) -> Self {
+        Self {
+            segments: parse_path(path.as_ref()),
+            code: None,
+            message: message.into(),
+            params: Map::new(),
+        }
+    }
+
+    /// Sets the machine code (e.g. `too_short`).
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Adds a parameter of the failed check (e.g. `min: 8`).
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Returns the dotted path, e.g. `items[2].qty`.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Index(index) => {
+                    let _ = write!(path, "[{}]", index);
+                }
+                Segment::Key(key) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+            }
+        }
+        path
+    }
+
+    /// Returns the RFC 6901 JSON Pointer, e.g. `/items/2/qty`.
+    pub fn pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            pointer.push('/');
+            match segment {
+                Segment::Index(index) => {
+                    let _ = write!(pointer, "{}", index);
+                }
+                Segment::Key(key) => pointer.push_str(&key.replace('~', "~0").replace('/', "~1")),
+            }
+        }
+        pointer
+    }
+
+    /// Returns the machine code, if any.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Returns the human-readable message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the parameters of the failed check.
+    pub fn params(&self) -> &Map<String, Value> {
+        &self.params
+    }
+}
+
+impl Serialize for Violation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("path", &self.path())?;
+        map.serialize_entry("pointer", &self.pointer())?;
+        if let Some(ref code) = self.code {
+            map.serialize_entry("code", code)?;
+        }
+        map.serialize_entry("message", &self.message)?;
+        if !self.params.is_empty() {
+            map.serialize_entry("params", &self.params)?;
+        }
+        map.end()
+    }
+}
+
+/// One step of a field path; indexes sort before keys.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Segment {
+    Index(usize),
+    Key(String),
+}
+
+/// Parses a dotted path or JSON Pointer into segments.
+fn parse_path(path: &str) -> Vec<Segment> {
+    if let Some(pointer) = path.strip_prefix('/') {
+        return pointer
+            .split('/')
+            .map(|token| match token.parse() {
+                Ok(index) => Segment::Index(index),
+                Err(_) => Segment::Key(token.replace("~1", "/").replace("~0", "~")),
+            })
+            .collect();
+    }
+
+    parse_dotted(path).unwrap_or_else(|| vec![Segment::Key(path.to_string())])
+}
+
+/// Parses `items[2].qty`; returns `None` for malformed paths.
+fn parse_dotted(path: &str) -> Option<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut key = String::new();
+    let mut chars = path.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '.' => {
+                if key.is_empty() && !matches!(segments.last(), Some(Segment::Index(_))) {
+                    return None;
+                }
+                if !key.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut key)));
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut key)));
+                }
+                let index: String = chars.by_ref().take_while(|&ch| ch != ']').collect();
+                segments.push(Segment::Index(index.parse().ok()?));
+            }
+            _ => key.push(ch),
+        }
+    }
+    if !key.is_empty() {
+        segments.push(Segment::Key(key));
+    }
+    Some(segments)
+}
@@ -0,0 +1,95 @@
+//! garde crate integration for error-envelope.
+//!
+//! Enable this module with the `garde-support` feature. A `garde::Report`
+//! converts into [`FieldErrors`] or a `VALIDATION_FAILED` `Error` with the
+//! same details shape as [`validation`](crate::validation), so clients see
+//! one field-error format whichever validation library a service uses.
+//!
+//! garde reports messages only, so violations carry no `code` or `params`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use error_envelope::Error;
+//! use garde::Validate;
+//!
+//! #[derive(Validate)]
+//! struct SignUp {
+//!     #[garde(length(min = 8))]
+//!     password: String,
+//! }
+//!
+//! fn sign_up(form: &SignUp) -> Result<(), Error> {
+//!     form.validate()?;
+//!     Ok(())
+//! }
+//!
+//! let err = sign_up(&SignUp { password: "short".into() }).unwrap_err();
+//! assert!(err.details.unwrap()["fields"]["password"].is_string());
+//! ```
+
+use crate::{Error, FieldErrors, Violation};
+use garde::Report;
+
+impl From<Report> for FieldErrors {
+    fn from(report: Report) -> Self {
+        field_errors(&report)
+    }
+}
+
+impl From<Report> for Error {
+    fn from(report: Report) -> Self {
+        crate::validation(field_errors(&report)).with_source(report)
+    }
+}
+
+/// garde paths display as `items[2].qty`, which `Violation` parses.
+fn field_errors(report: &Report) -> FieldErrors {
+    report
+        .iter()
+        .map(|(path, err)| Violation::new(path.to_string(), err.message()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Code, Error};
+    use garde::Validate;
+
+    #[derive(Validate)]
+    struct Item {
+        #[garde(range(min = 1))]
+        qty: i32,
+    }
+
+    #[derive(Validate)]
+    struct Order {
+        #[garde(email)]
+        email: String,
+        #[garde(dive)]
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn test_report_to_envelope() {
+        let order = Order {
+            email: "not-an-email".to_string(),
+            items: vec![Item { qty: 1 }, Item { qty: 0 }],
+        };
+        let err = Error::from(order.validate().unwrap_err());
+
+        assert_eq!(err.code, Code::ValidationFailed);
+        assert_eq!(err.status, 400);
+
+        let details = err.details.unwrap();
+        let fields = details["fields"].as_object().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert!(fields["email"].is_string());
+        assert!(fields["items[1].qty"].is_string());
+
+        let violation = &details["violations"][1];
+        assert_eq!(violation["path"], "items[1].qty");
+        assert_eq!(violation["pointer"], "/items/1/qty");
+        assert!(violation.get("code").is_none());
+    }
+}
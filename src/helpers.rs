@@ -1,4 +1,4 @@
-use crate::{Code, Error};
+use crate::{Code, Error, FieldErrors};
 
 /// Helper constructors for common error types.
 impl Error {
@@ -59,6 +59,11 @@ impl Error {
         Self::new(Code::PayloadTooLarge, 413, message).with_retryable(false)
     }
 
+    /// Creates an unsupported media type error (415).
+    pub fn unsupported_media_type(message: impl Into<String>) -> Self {
+        Self::new(Code::UnsupportedMediaType, 415, message).with_retryable(false)
+    }
+
     /// Creates an unprocessable entity error (422).
     pub fn unprocessable_entity(message: impl Into<String>) -> Self {
         Self::new(Code::UnprocessableEntity, 422, message).with_retryable(false)
@@ -100,7 +105,7 @@ impl Error {
     }
 }
 
-// Formatted constructors (using format! macro)
+// Formatted constructors (using format! macro); `envelope!` formats for you
 
 /// Creates an internal server error with formatted message.
 pub fn internalf(message: impl Into<String>) -> Error {
@@ -144,37 +149,36 @@ pub fn unavailablef(message: impl Into<String>) -> Error {
 
 // Additional helpers
 
-use serde_json::json;
-use std::collections::HashMap;
-
-/// Field-level validation errors.
-pub type FieldErrors = HashMap<String, String>;
-
 /// Creates a validation error with field-level details.
-pub fn validation(fields: FieldErrors) -> Error {
+///
+/// Accepts a [`FieldErrors`] or, for simple cases, a map of field to message.
+/// Details carry `fields` (first message per field) and `violations`.
+pub fn validation(fields: impl Into<FieldErrors>) -> Error {
+    let details = serde_json::to_value(fields.into()).expect("field errors serialize to JSON");
     Error::new(Code::ValidationFailed, 400, "")
-        .with_details(json!({"fields": fields}))
+        .with_details(details)
         .with_retryable(false)
 }
 
 /// Maps arbitrary errors into an Error.
 ///
-/// Handles common error types and wraps unknown errors as Internal.
+/// Known error types are classified by downcasting (the error itself and
+/// its source chain):
+/// - `std::io::Error` by `ErrorKind` (e.g. `TimedOut` → Timeout,
+///   `NotFound` → NotFound, `PermissionDenied` → Forbidden,
+///   `ConnectionRefused` → Unavailable)
+/// - `serde_json::Error` → BadRequest (Internal for I/O failures)
+/// - `tokio::time::error::Elapsed` → Timeout (with `tokio-support`)
+///
+/// Other errors fall back to message heuristics for timeouts and
+/// cancellation, then to Internal. The error message is kept as the cause;
+/// [`Classifier::classify`](crate::Classifier::classify) also keeps the
+/// error itself.
+///
+/// Rules installed with [`set_classifier`](crate::set_classifier) are
+/// consulted before the built-in ones.
 pub fn from(err: impl std::error::Error + 'static) -> Error {
-    let err_str = err.to_string().to_lowercase();
-
-    // Check for timeout errors
-    if err_str.contains("timeout") || err_str.contains("timed out") {
-        return Error::timeout("");
-    }
-
-    // Check for canceled errors
-    if err_str.contains("cancel") {
-        return Error::new(Code::Canceled, 499, "").with_retryable(false);
-    }
-
-    // Default to wrapping as internal error
-    Error::wrap(Code::Internal, 500, "", err).with_retryable(false)
+    crate::classify::classify_global(&err)
 }
 
 /// Checks if an error has the given code.
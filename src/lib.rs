@@ -21,17 +21,86 @@
 //! assert_eq!(err.status, 404);
 //! ```
 
+mod classify;
 mod codes;
 mod error;
+mod ext;
+mod fields;
 mod helpers;
+mod macros;
+mod problem;
+mod response;
 mod tests;
+mod upstream;
 
-pub use codes::Code;
+pub use classify::{set_classifier, Classifier};
+pub use codes::{Code, CustomCode};
 pub use error::Error;
+pub use ext::{OptionExt, ResultExt};
+pub use fields::{FieldErrors, Violation};
 pub use helpers::*;
+pub use problem::ProblemDetails;
+pub use response::{
+    default_format, default_redaction, set_default_format, set_default_redaction, Format, Redaction,
+};
+pub use upstream::UpstreamCode;
+
+#[cfg(any(feature = "actix-support", feature = "axum-support"))]
+mod rejection;
+
+#[cfg(feature = "actix-support")]
+pub mod actix_support;
 
 #[cfg(feature = "axum-support")]
 pub mod axum_support;
 
+#[cfg(feature = "tower-support")]
+pub mod tower_support;
+
 #[cfg(feature = "anyhow-support")]
 mod anyhow_support;
+
+#[cfg(feature = "garde-support")]
+mod garde_support;
+
+#[cfg(any(feature = "tokio-support", feature = "tower-support"))]
+mod random;
+
+#[cfg(feature = "tokio-support")]
+mod retry;
+#[cfg(feature = "tokio-support")]
+pub use retry::{retry, RetryPolicy};
+
+#[cfg(feature = "tracing-support")]
+pub mod tracing_support;
+
+#[cfg(feature = "opentelemetry-support")]
+pub mod opentelemetry_support;
+
+#[cfg(feature = "tonic-support")]
+mod tonic_support;
+
+#[cfg(feature = "reqwest-support")]
+pub mod reqwest_support;
+
+#[cfg(feature = "validator-support")]
+pub mod validator_support;
+
+#[cfg(feature = "derive")]
+pub use error_envelope_derive::IntoEnvelope;
+
+// Support code for generated `IntoEnvelope` impls; not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+
+    use crate::Error;
+
+    /// Attaches a cause, accepting both `std::error::Error` types and `anyhow::Error`.
+    pub fn with_cause(
+        err: Error,
+        cause: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Error {
+        err.with_boxed_cause(cause.into())
+    }
+}
@@ -0,0 +1,115 @@
+/// Builds an [`Error`](crate::Error) with a formatted message.
+///
+/// The first argument is a built-in [`Code`](crate::Code) variant name, or
+/// any other expression convertible into `Code`. A bare identifier always
+/// names a built-in variant, so pass custom codes as
+/// `Code::Custom(NAME)` or by path. The message takes `format!` arguments
+/// and may be omitted to use the code's default message. The status and
+/// retryability come from the code.
+///
+/// `details`, `trace_id`, `retryable`, `retry_after` and `status` can be
+/// set after a `;`.
+///
+/// # Example
+/// ```
+/// use error_envelope::{envelope, Code};
+///
+/// let id = 42;
+/// let err = envelope!(NotFound, "user {} not found", id);
+/// assert_eq!(err.code, Code::NotFound);
+/// assert_eq!(err.message, "user 42 not found");
+///
+/// let err = envelope!(
+///     Conflict, "email {id} taken";
+///     details = serde_json::json!({"field": "email"}),
+///     trace_id = "abc-123"
+/// );
+/// assert_eq!(err.status, 409);
+/// assert_eq!(err.trace_id.as_deref(), Some("abc-123"));
+/// ```
+#[macro_export]
+macro_rules! envelope {
+    (@build $code:expr, $message:expr $(; $($key:ident = $value:expr),+)?) => {{
+        #[allow(unused_mut)]
+        let mut err = $crate::Error::new($code, 0, $message);
+        $($( err = $crate::envelope!(@with err, $key, $value); )+)?
+        err
+    }};
+    (@with $err:ident, details, $value:expr) => { $err.with_details($value) };
+    (@with $err:ident, trace_id, $value:expr) => { $err.with_trace_id($value) };
+    (@with $err:ident, retryable, $value:expr) => { $err.with_retryable($value) };
+    (@with $err:ident, retry_after, $value:expr) => { $err.with_retry_after($value) };
+    (@with $err:ident, status, $value:expr) => { $err.with_status($value) };
+
+    ($code:ident $(; $($key:ident = $value:expr),+)?) => {
+        $crate::envelope!(@build $crate::Code::$code, "" $(; $($key = $value),+)?)
+    };
+    ($code:ident, $fmt:literal $(, $arg:expr)* $(; $($key:ident = $value:expr),+)?) => {
+        $crate::envelope!(
+            @build $crate::Code::$code, ::std::format!($fmt $(, $arg)*)
+            $(; $($key = $value),+)?
+        )
+    };
+    ($code:expr $(; $($key:ident = $value:expr),+)?) => {
+        $crate::envelope!(@build $crate::Code::from($code), "" $(; $($key = $value),+)?)
+    };
+    ($code:expr, $fmt:literal $(, $arg:expr)* $(; $($key:ident = $value:expr),+)?) => {
+        $crate::envelope!(
+            @build $crate::Code::from($code), ::std::format!($fmt $(, $arg)*)
+            $(; $($key = $value),+)?
+        )
+    };
+}
+
+/// Returns early with an [`envelope!`] error.
+///
+/// Takes the same arguments as [`envelope!`]. The error is converted with
+/// `From`, so it works in functions returning any error type that
+/// `Error` converts into.
+///
+/// # Example
+/// ```
+/// use error_envelope::{bail_envelope, Error};
+///
+/// fn find_user(id: u64) -> Result<String, Error> {
+///     if id == 0 {
+///         bail_envelope!(NotFound, "user {} not found", id);
+///     }
+///     Ok(format!("user {}", id))
+/// }
+///
+/// assert_eq!(find_user(0).unwrap_err().message, "user 0 not found");
+/// ```
+#[macro_export]
+macro_rules! bail_envelope {
+    ($($args:tt)+) => {
+        return ::core::result::Result::Err(::core::convert::From::from(
+            $crate::envelope!($($args)+),
+        ))
+    };
+}
+
+/// Returns early with an [`envelope!`] error unless a condition holds.
+///
+/// The message is only formatted when the condition is false.
+///
+/// # Example
+/// ```
+/// use error_envelope::{ensure_envelope, Code, Error};
+///
+/// fn delete_post(role: &str) -> Result<(), Error> {
+///     ensure_envelope!(role == "admin", Forbidden, "role {} cannot delete posts", role);
+///     Ok(())
+/// }
+///
+/// assert_eq!(delete_post("guest").unwrap_err().code, Code::Forbidden);
+/// assert!(delete_post("admin").is_ok());
+/// ```
+#[macro_export]
+macro_rules! ensure_envelope {
+    ($cond:expr, $($args:tt)+) => {
+        if !$cond {
+            $crate::bail_envelope!($($args)+);
+        }
+    };
+}
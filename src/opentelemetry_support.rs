@@ -0,0 +1,201 @@
+//! OpenTelemetry span integration for error-envelope.
+//!
+//! Enable this module with the `opentelemetry-support` feature. Every
+//! `Error` rendered into a response (through `into_http_response*` or the
+//! Axum `IntoResponse` impl) is recorded on the active span:
+//!
+//! - Attributes `error.code`, `http.response.status_code` and `error.retryable`
+//! - For 5xx (and optionally 4xx): span status `Error`, attribute
+//!   `error.type`, and an `exception` event carrying the cause chain
+//!
+//! # Example
+//!
+//! ```rust
+//! use error_envelope::{opentelemetry_support, Error};
+//!
+//! // Also mark spans as failed for 4xx responses
+//! opentelemetry_support::set_client_errors_as_failures(true);
+//!
+//! // Record an error that never becomes a response
+//! Error::unavailable("cache offline").record_in_active_span();
+//! ```
+
+use crate::Error;
+use opentelemetry::trace::{get_active_span, SpanRef, Status};
+use opentelemetry::{Array, KeyValue, StringValue, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CLIENT_ERRORS_AS_FAILURES: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether 4xx errors also set the span status to `Error`.
+///
+/// Off by default: client errors are usually expected behavior rather than
+/// failures of the service.
+pub fn set_client_errors_as_failures(enabled: bool) {
+    CLIENT_ERRORS_AS_FAILURES.store(enabled, Ordering::Relaxed);
+}
+
+impl Error {
+    /// Records this error on the currently active span.
+    pub fn record_in_active_span(&self) {
+        get_active_span(|span| self.record_in_span(&span));
+    }
+
+    /// Records this error on the given span.
+    pub fn record_in_span(&self, span: &SpanRef<'_>) {
+        let status = self.response_status().as_u16();
+        span.set_attribute(KeyValue::new("error.code", self.code.as_str()));
+        span.set_attribute(KeyValue::new(
+            "http.response.status_code",
+            i64::from(status),
+        ));
+        span.set_attribute(KeyValue::new("error.retryable", self.retryable));
+
+        let failure =
+            status >= 500 || (status >= 400 && CLIENT_ERRORS_AS_FAILURES.load(Ordering::Relaxed));
+        if !failure {
+            return;
+        }
+
+        span.set_attribute(KeyValue::new("error.type", self.code.as_str()));
+        span.set_status(Status::error(self.message.clone()));
+
+        // Walk the full source chain, outermost first
+        let mut chain: Vec<StringValue> = Vec::new();
+        let mut current = std::error::Error::source(self);
+        while let Some(err) = current {
+            chain.push(err.to_string().into());
+            current = err.source();
+        }
+
+        let mut attributes = vec![
+            KeyValue::new("exception.type", self.code.as_str()),
+            KeyValue::new("exception.message", self.to_string()),
+        ];
+        if !chain.is_empty() {
+            attributes.push(KeyValue::new(
+                "error.cause_chain",
+                Value::Array(Array::String(chain)),
+            ));
+        }
+        span.add_event("exception", attributes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{mark_span_as_active, Span, SpanContext};
+    use std::borrow::Cow;
+    use std::sync::{Arc, Mutex};
+    use std::time::SystemTime;
+
+    #[derive(Default)]
+    struct Recorded {
+        attributes: Vec<KeyValue>,
+        status: Option<Status>,
+        events: Vec<(String, Vec<KeyValue>)>,
+    }
+
+    /// Span that records everything into shared state.
+    struct TestSpan(Arc<Mutex<Recorded>>, SpanContext);
+
+    impl Span for TestSpan {
+        fn add_event_with_timestamp<T>(&mut self, name: T, _: SystemTime, attributes: Vec<KeyValue>)
+        where
+            T: Into<Cow<'static, str>>,
+        {
+            let name = name.into().into_owned();
+            self.0.lock().unwrap().events.push((name, attributes));
+        }
+        fn span_context(&self) -> &SpanContext {
+            &self.1
+        }
+        fn is_recording(&self) -> bool {
+            true
+        }
+        fn set_attribute(&mut self, attribute: KeyValue) {
+            self.0.lock().unwrap().attributes.push(attribute);
+        }
+        fn set_status(&mut self, status: Status) {
+            self.0.lock().unwrap().status = Some(status);
+        }
+        fn update_name<T>(&mut self, _: T)
+        where
+            T: Into<Cow<'static, str>>,
+        {
+        }
+        fn add_link(&mut self, _: SpanContext, _: Vec<KeyValue>) {}
+        fn end_with_timestamp(&mut self, _: SystemTime) {}
+    }
+
+    fn record(err: Error) -> Recorded {
+        let recorded = Arc::new(Mutex::new(Recorded::default()));
+        {
+            let span = TestSpan(recorded.clone(), SpanContext::empty_context());
+            let _guard = mark_span_as_active(span);
+            let _ = err.into_http_response();
+        }
+        Arc::try_unwrap(recorded)
+            .ok()
+            .unwrap()
+            .into_inner()
+            .unwrap()
+    }
+
+    fn attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a Value> {
+        attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| &kv.value)
+    }
+
+    #[test]
+    fn marks_server_errors() {
+        let cause = std::io::Error::other("disk full");
+        let recorded = record(Error::internal("write failed").with_source(cause));
+
+        assert_eq!(
+            recorded.status,
+            Some(Status::error("write failed".to_string()))
+        );
+        assert_eq!(
+            attribute(&recorded.attributes, "error.code"),
+            Some(&Value::from("INTERNAL"))
+        );
+        assert_eq!(
+            attribute(&recorded.attributes, "http.response.status_code"),
+            Some(&Value::I64(500))
+        );
+
+        let (name, attributes) = &recorded.events[0];
+        assert_eq!(name, "exception");
+        assert_eq!(
+            attribute(attributes, "error.cause_chain"),
+            Some(&Value::Array(Array::String(vec!["disk full".into()])))
+        );
+    }
+
+    #[test]
+    fn records_sent_status_for_invalid_status() {
+        let recorded = record(Error::not_found("gone").with_status(42));
+
+        assert_eq!(
+            attribute(&recorded.attributes, "http.response.status_code"),
+            Some(&Value::I64(500))
+        );
+        assert_eq!(recorded.status, Some(Status::error("gone".to_string())));
+    }
+
+    #[test]
+    fn leaves_client_errors_unset() {
+        let recorded = record(Error::not_found("user not found"));
+
+        assert!(recorded.status.is_none());
+        assert!(recorded.events.is_empty());
+        assert_eq!(
+            attribute(&recorded.attributes, "error.retryable"),
+            Some(&Value::Bool(false))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::error::parse_code;
+use crate::{Code, Error};
+use serde::{Deserialize, Serialize};
+
+/// RFC 9457 Problem Details representation of an `Error`.
+///
+/// The standard members (`type`, `title`, `status`, `detail`, `instance`)
+/// are followed by extension members carrying the envelope fields, so
+/// clients that understand either format see the same information.
+///
+/// Deserialization is tolerant: a missing or unknown `code` falls back to
+/// the code for `status`, and a missing `retryable` to that code's default.
+///
+/// # Example
+/// ```
+/// use error_envelope::Error;
+///
+/// let problem = Error::not_found("user 123 not found")
+///     .with_trace_id("abc-123")
+///     .to_problem_details();
+///
+/// assert_eq!(problem.status, 404);
+/// assert_eq!(problem.title, "Not found");
+/// assert_eq!(problem.detail, "user 123 not found");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "WireProblemDetails")]
+pub struct ProblemDetails {
+    /// URI reference identifying the problem type.
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    /// Short, human-readable summary of the problem type.
+    pub title: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// Human-readable explanation specific to this occurrence.
+    pub detail: String,
+    /// URI reference identifying this specific occurrence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+
+    /// Machine-readable error code (extension member).
+    pub code: Code,
+    /// Trace ID for distributed tracing (extension member).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// Whether the client should retry this request (extension member).
+    pub retryable: bool,
+    /// Structured details (extension member).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+/// Problem Details as read from the wire, before resolving the code.
+#[derive(Deserialize)]
+struct WireProblemDetails {
+    #[serde(rename = "type", default = "about_blank")]
+    type_uri: String,
+    title: String,
+    status: u16,
+    detail: String,
+    #[serde(default)]
+    instance: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    trace_id: Option<String>,
+    #[serde(default)]
+    retryable: Option<bool>,
+    #[serde(default)]
+    details: Option<serde_json::Value>,
+}
+
+fn about_blank() -> String {
+    ProblemDetails::ABOUT_BLANK.to_string()
+}
+
+impl From<WireProblemDetails> for ProblemDetails {
+    fn from(wire: WireProblemDetails) -> Self {
+        let code = match wire.code {
+            Some(name) => parse_code(&name, Some(wire.status)),
+            None => Code::from_status(wire.status),
+        };
+        ProblemDetails {
+            type_uri: wire.type_uri,
+            title: wire.title,
+            status: wire.status,
+            detail: wire.detail,
+            instance: wire.instance,
+            code,
+            trace_id: wire.trace_id,
+            retryable: wire
+                .retryable
+                .unwrap_or_else(|| code.is_retryable_default()),
+            details: wire.details,
+        }
+    }
+}
+
+impl ProblemDetails {
+    /// Default problem type when no more specific URI is available.
+    pub const ABOUT_BLANK: &'static str = "about:blank";
+
+    /// Sets the problem type URI.
+    pub fn with_type(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Sets the occurrence URI (typically the request path).
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+impl Error {
+    /// Converts the error into its RFC 9457 Problem Details representation.
+    ///
+    /// `status` is the HTTP status the error is sent with, so an invalid
+    /// `Error::status` reports 500.
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        ProblemDetails {
+            type_uri: ProblemDetails::ABOUT_BLANK.to_string(),
+            title: self.code.default_message().to_string(),
+            status: self.response_status().as_u16(),
+            detail: self.message.clone(),
+            instance: None,
+            code: self.code,
+            trace_id: self.trace_id.clone(),
+            retryable: self.retryable,
+            details: self.details.clone(),
+        }
+    }
+}
+
+impl From<&Error> for ProblemDetails {
+    fn from(err: &Error) -> Self {
+        err.to_problem_details()
+    }
+}
@@ -0,0 +1,23 @@
+//! Non-cryptographic randomness for backoff jitter and generated IDs.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Returns 64 random bits.
+///
+/// `RandomState` is seeded randomly per instance; mixing in a counter and
+/// the clock keeps consecutive calls distinct even if seeding is weak.
+pub(crate) fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(count);
+    hasher.write_u64(nanos);
+    hasher.finish()
+}
@@ -0,0 +1,30 @@
+//! Shared mapping of framework extractor rejections into `Error`s.
+
+use crate::Error;
+
+/// Maps a rejection without field information by its HTTP status.
+pub(crate) fn status_error(status: u16, message: String) -> Error {
+    match status {
+        413 => Error::payload_too_large(message),
+        415 => Error::unsupported_media_type(message),
+        422 => Error::validation(message),
+        500..=599 => Error::internal(message),
+        _ => Error::bad_request(message),
+    }
+}
+
+/// Builds a validation error for a single offending field.
+pub(crate) fn field_error(field: String, reason: String, message: String) -> Error {
+    let mut fields = crate::FieldErrors::new();
+    fields.insert(field, reason);
+    let mut err = crate::validation(fields);
+    err.message = message;
+    err
+}
+
+/// Extracts the field name from serde messages such as "missing field `page`".
+pub(crate) fn named_field(message: &str) -> Option<String> {
+    let start = message.find("field `")? + "field `".len();
+    let len = message[start..].find('`')?;
+    Some(message[start..start + len].to_string())
+}
@@ -0,0 +1,289 @@
+//! reqwest client integration for error-envelope.
+//!
+//! Enable this module with the `reqwest-support` feature.
+//!
+//! - [`ResponseExt::error_envelope`] turns an error response into the
+//!   `Error` the other service sent, or synthesizes one from the status
+//!   when the body is not an envelope.
+//! - `From<reqwest::Error> for Error` maps transport failures to
+//!   `DOWNSTREAM_ERROR` or `DOWNSTREAM_TIMEOUT`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use error_envelope::{reqwest_support::ResponseExt, Error};
+//!
+//! async fn fetch_user(client: &reqwest::Client, id: u64) -> Result<String, Error> {
+//!     let response = client
+//!         .get(format!("http://users/users/{}", id))
+//!         .send()
+//!         .await?
+//!         .error_envelope()
+//!         .await?;
+//!     Ok(response.text().await?)
+//! }
+//! ```
+
+use crate::error::parse_code;
+use crate::response::REQUEST_ID_HEADER;
+use crate::{Code, Error};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::Response;
+use serde::Deserialize;
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Extension methods for `reqwest::Response`.
+pub trait ResponseExt {
+    /// Passes successful responses through and decodes error responses.
+    ///
+    /// For 4xx/5xx responses the body is read as an envelope (or Problem
+    /// Details); otherwise an `Error` is built from the status and body
+    /// text. `Retry-After` and `X-Request-Id` headers fill in `retry_after`
+    /// and `trace_id` when the body does not carry them.
+    fn error_envelope(self) -> Pin<Box<dyn Future<Output = Result<Response, Error>> + Send>>;
+}
+
+impl ResponseExt for Response {
+    fn error_envelope(self) -> Pin<Box<dyn Future<Output = Result<Response, Error>> + Send>> {
+        Box::pin(async move {
+            let status = self.status();
+            if !status.is_client_error() && !status.is_server_error() {
+                return Ok(self);
+            }
+
+            let headers = self.headers().clone();
+            let body = self.bytes().await?;
+            Err(decode(status.as_u16(), &headers, &body))
+        })
+    }
+}
+
+/// Builds the `Error` described by an error response.
+fn decode(status: u16, headers: &HeaderMap, body: &[u8]) -> Error {
+    let json = serde_json::from_slice::<Value>(body)
+        .ok()
+        .filter(Value::is_object);
+    let mut err = match json {
+        Some(json) if is_problem(&json) => match Problem::deserialize(&json) {
+            Ok(problem) => problem.into_error(status),
+            Err(_) => Error::new(Code::from_status(status), status, ""),
+        },
+        Some(mut json) => {
+            // Codes this service doesn't know fall back to the HTTP status's code
+            json["status"] = status.into();
+            match Error::deserialize(&json) {
+                Ok(err) => err,
+                Err(_) => Error::new(Code::from_status(status), status, body_message(body)),
+            }
+        }
+        None => Error::new(Code::from_status(status), status, body_message(body)),
+    };
+    err.status = status;
+
+    if err.trace_id.is_none() {
+        err.trace_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+    }
+    if err.retry_after.is_none() {
+        err.retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+    }
+    err
+}
+
+/// Whether a JSON body is RFC 9457 Problem Details rather than an envelope.
+fn is_problem(json: &Value) -> bool {
+    json.get("message").is_none() && (json.get("title").is_some() || json.get("detail").is_some())
+}
+
+/// Problem Details body, with or without the error-envelope extension
+/// members.
+///
+/// Services that don't use error-envelope only send the standard members,
+/// so every member is optional. The HTTP status wins over the `status`
+/// member.
+#[derive(Deserialize)]
+struct Problem {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    detail: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    trace_id: Option<String>,
+    #[serde(default)]
+    retryable: Option<bool>,
+    #[serde(default)]
+    details: Option<Value>,
+}
+
+impl Problem {
+    fn into_error(self, status: u16) -> Error {
+        let code = match self.code {
+            Some(name) => parse_code(&name, Some(status)),
+            None => Code::from_status(status),
+        };
+        let message = self.detail.or(self.title).unwrap_or_default();
+        let mut err = Error::new(code, status, message);
+        if let Some(retryable) = self.retryable {
+            err.retryable = retryable;
+        }
+        err.trace_id = self.trace_id;
+        err.details = self.details;
+        err
+    }
+}
+
+/// Longest body text used as the message of a synthesized error.
+const MAX_BODY_MESSAGE: usize = 512;
+
+/// Uses a short plain-text body as the message; anything else falls back
+/// to the code's default message.
+fn body_message(body: &[u8]) -> String {
+    match std::str::from_utf8(body) {
+        Ok(text) if text.trim().len() <= MAX_BODY_MESSAGE => text.trim().to_string(),
+        _ => String::new(),
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        // Raised by error_for_status(): describe the remote status instead
+        if let Some(status) = err.status() {
+            let status = status.as_u16();
+            return Error::new(Code::from_status(status), status, "").with_source(err);
+        }
+
+        let service = err
+            .url()
+            .and_then(|url| url.host_str())
+            .unwrap_or_default()
+            .to_string();
+        let downstream = if err.is_timeout() {
+            Error::downstream_timeout(service, &err)
+        } else {
+            Error::downstream(service, &err)
+        };
+        downstream.with_source(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> Response {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Response::from(builder.body(body.to_string()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_decodes_envelope() {
+        let body = serde_json::to_string(
+            &Error::rate_limited("slow down").with_details(serde_json::json!({"limit": 100})),
+        )
+        .unwrap();
+        let resp = response(
+            429,
+            &[("retry-after", "30"), ("x-request-id", "abc-123")],
+            &body,
+        );
+        let err = resp.error_envelope().await.unwrap_err();
+
+        assert_eq!(err.code, Code::RateLimited);
+        assert_eq!(err.status, 429);
+        assert_eq!(err.message, "slow down");
+        assert_eq!(err.trace_id.as_deref(), Some("abc-123"));
+        assert_eq!(err.retry_after, Some(Duration::from_secs(30)));
+        assert_eq!(err.details.unwrap()["limit"], 100);
+    }
+
+    #[tokio::test]
+    async fn test_decodes_problem_details() {
+        let problem = Error::forbidden("no access")
+            .with_trace_id("abc-123")
+            .to_problem_details();
+        let resp = response(403, &[], &serde_json::to_string(&problem).unwrap());
+        let err = resp.error_envelope().await.unwrap_err();
+
+        assert_eq!(err.code, Code::Forbidden);
+        assert_eq!(err.message, "no access");
+        assert_eq!(err.trace_id.as_deref(), Some("abc-123"));
+    }
+
+    #[tokio::test]
+    async fn test_decodes_standard_problem_details() {
+        let body = r#"{"type":"https://example.com/out-of-credit","title":"Out of credit","status":403,"detail":"Balance is 30, cost is 50"}"#;
+        let err = response(403, &[], body).error_envelope().await.unwrap_err();
+
+        assert_eq!(err.code, Code::Forbidden);
+        assert_eq!(err.status, 403);
+        assert_eq!(err.message, "Balance is 30, cost is 50");
+
+        let body = r#"{"title":"Service Unavailable","status":503}"#;
+        let err = response(503, &[], body).error_envelope().await.unwrap_err();
+        assert_eq!(err.code, Code::Unavailable);
+        assert_eq!(err.message, "Service Unavailable");
+        assert!(err.retryable);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_code_falls_back_to_status() {
+        let body = r#"{"code":"USER_SUSPENDED","message":"account suspended","retryable":false}"#;
+        let err = response(404, &[], body).error_envelope().await.unwrap_err();
+        assert_eq!(err.code, Code::NotFound);
+        assert_eq!(err.status, 404);
+        assert_eq!(err.message, "account suspended");
+
+        let body = r#"{"type":"about:blank","title":"Suspended","status":503,"detail":"try later","code":"USER_SUSPENDED","retryable":true}"#;
+        let err = response(503, &[], body).error_envelope().await.unwrap_err();
+        assert_eq!(err.code, Code::Unavailable);
+        assert_eq!(err.message, "try later");
+        assert!(err.retryable);
+    }
+
+    #[tokio::test]
+    async fn test_synthesizes_from_status() {
+        let resp = response(503, &[], "upstream connect error");
+        let err = resp.error_envelope().await.unwrap_err();
+
+        assert_eq!(err.code, Code::Unavailable);
+        assert_eq!(err.message, "upstream connect error");
+        assert!(err.retryable);
+
+        let resp = response(404, &[], "<html>not found</html>".repeat(40).as_str());
+        let err = resp.error_envelope().await.unwrap_err();
+        assert_eq!(err.code, Code::NotFound);
+        assert_eq!(err.message, "Not found");
+    }
+
+    #[tokio::test]
+    async fn test_passes_success_through() {
+        let resp = response(200, &[], "ok");
+        let resp = resp.error_envelope().await.unwrap();
+        assert_eq!(resp.text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_transport_error() {
+        // Nothing listens on port 1
+        let err = reqwest::get("http://127.0.0.1:1/").await.unwrap_err();
+        let err = Error::from(err);
+
+        assert_eq!(err.code, Code::DownstreamError);
+        assert_eq!(err.details.unwrap()["service"], "127.0.0.1");
+        assert!(err.retryable);
+    }
+}
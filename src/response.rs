@@ -0,0 +1,255 @@
+use crate::Error;
+use http::{header, HeaderValue, StatusCode};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Header used to echo the trace ID back to the client.
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Wire format used when rendering an `Error` as an HTTP response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Format {
+    /// The crate's own envelope (`application/json`).
+    #[default]
+    Envelope,
+    /// RFC 9457 Problem Details (`application/problem+json`).
+    Problem,
+}
+
+impl Format {
+    /// Returns the `Content-Type` for this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Envelope => "application/json",
+            Format::Problem => "application/problem+json",
+        }
+    }
+}
+
+/// Policy for hiding internal details from clients.
+///
+/// Redaction only affects what goes on the wire; the original `Error` is
+/// kept in the response extensions so logging middleware still sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Redaction {
+    /// Send errors as-is.
+    #[default]
+    Off,
+    /// For 5xx responses, replace `message` with the code's default
+    /// message and drop `details`.
+    ServerErrors,
+}
+
+impl Redaction {
+    /// Returns whether this policy redacts the given error.
+    pub fn applies_to(&self, err: &Error) -> bool {
+        match self {
+            Redaction::Off => false,
+            Redaction::ServerErrors => err.response_status().is_server_error(),
+        }
+    }
+}
+
+static DEFAULT_FORMAT: AtomicU8 = AtomicU8::new(0);
+static DEFAULT_REDACTION: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the process-wide response format.
+///
+/// Applies to `into_http_response` and the Axum integration unless a
+/// per-router override is installed.
+pub fn set_default_format(format: Format) {
+    let value = match format {
+        Format::Envelope => 0,
+        Format::Problem => 1,
+    };
+    DEFAULT_FORMAT.store(value, Ordering::Relaxed);
+}
+
+/// Returns the process-wide response format.
+pub fn default_format() -> Format {
+    match DEFAULT_FORMAT.load(Ordering::Relaxed) {
+        1 => Format::Problem,
+        _ => Format::Envelope,
+    }
+}
+
+/// Sets the process-wide redaction policy.
+///
+/// Applies to `into_http_response` and the Axum integration unless a
+/// per-router override is installed.
+pub fn set_default_redaction(redaction: Redaction) {
+    let value = match redaction {
+        Redaction::Off => 0,
+        Redaction::ServerErrors => 1,
+    };
+    DEFAULT_REDACTION.store(value, Ordering::Relaxed);
+}
+
+/// Returns the process-wide redaction policy.
+pub fn default_redaction() -> Redaction {
+    match DEFAULT_REDACTION.load(Ordering::Relaxed) {
+        1 => Redaction::ServerErrors,
+        _ => Redaction::Off,
+    }
+}
+
+/// Framework-agnostic conversion into `http::Response`.
+impl Error {
+    /// Converts the error into an `http::Response` with a JSON body.
+    ///
+    /// The response carries the same status, body and headers as the Axum
+    /// `IntoResponse` implementation, so hyper, tower or custom servers
+    /// produce identical wire output. The original error is stored in the
+    /// response extensions.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::Error;
+    /// use std::time::Duration;
+    ///
+    /// let response = Error::rate_limited("slow down")
+    ///     .with_retry_after(Duration::from_secs(30))
+    ///     .into_http_response();
+    ///
+    /// assert_eq!(response.status(), 429);
+    /// assert_eq!(response.headers()["retry-after"], "30");
+    /// ```
+    pub fn into_http_response(self) -> http::Response<Vec<u8>> {
+        self.into_http_response_with(|body| body)
+    }
+
+    /// Converts the error into an `http::Response`, building the body type
+    /// from the serialized JSON bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::Error;
+    ///
+    /// let response = Error::not_found("user not found")
+    ///     .into_http_response_with(|bytes| String::from_utf8(bytes).unwrap());
+    ///
+    /// assert!(response.body().contains("NOT_FOUND"));
+    /// ```
+    pub fn into_http_response_with<B, F>(self, body: F) -> http::Response<B>
+    where
+        F: FnOnce(Vec<u8>) -> B,
+    {
+        self.into_http_response_as(default_format(), body)
+    }
+
+    /// Converts the error into an `http::Response` using an explicit format.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::{Error, Format};
+    ///
+    /// let response = Error::forbidden("no access").into_http_response_as(Format::Problem, |b| b);
+    /// assert_eq!(response.headers()["content-type"], "application/problem+json");
+    /// ```
+    #[cfg_attr(not(feature = "tower-support"), allow(unused_mut))]
+    pub fn into_http_response_as<B, F>(mut self, format: Format, body: F) -> http::Response<B>
+    where
+        F: FnOnce(Vec<u8>) -> B,
+    {
+        // Inside a TraceIdLayer, fall back to the request's trace ID
+        #[cfg(feature = "tower-support")]
+        if self.trace_id.is_none() {
+            self.trace_id = crate::tower_support::current_trace_id();
+        }
+
+        self.report();
+
+        let status = self.response_status();
+        let bytes = self.render_body(format, default_redaction(), None);
+
+        let mut response = http::Response::new(body(bytes));
+        *response.status_mut() = status;
+
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(format.content_type()),
+        );
+
+        // Add Retry-After header if specified
+        if let Some(duration) = self.retry_after {
+            let seconds = duration.as_secs().max(1);
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+
+        // Add X-Request-Id header if trace ID is present
+        if let Some(ref trace_id) = self.trace_id {
+            if let Ok(value) = HeaderValue::from_str(trace_id) {
+                headers.insert(REQUEST_ID_HEADER, value);
+            }
+        }
+
+        // Keep the original error for logging and re-rendering layers
+        response.extensions_mut().insert(self);
+        response
+    }
+
+    /// Returns a copy that is safe to show to clients.
+    ///
+    /// The message is replaced with the code's default message and details
+    /// are dropped; code, status, trace ID and retry hints are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::Error;
+    ///
+    /// let err = Error::internal("db: connection to 10.0.0.5 refused")
+    ///     .with_details(serde_json::json!({"host": "10.0.0.5"}));
+    /// let safe = err.redacted();
+    ///
+    /// assert_eq!(safe.message, "Internal error");
+    /// assert!(safe.details.is_none());
+    /// ```
+    pub fn redacted(&self) -> Error {
+        let mut err = Error::new(self.code, self.status, "");
+        err.trace_id = self.trace_id.clone();
+        err.retryable = self.retryable;
+        err.retry_after = self.retry_after;
+        err
+    }
+
+    /// Returns the status the response is sent with: `status`, or 500 when
+    /// it is not a valid HTTP status.
+    pub(crate) fn response_status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Logs and traces the error as it becomes a response.
+    pub(crate) fn report(&self) {
+        #[cfg(feature = "tracing-support")]
+        self.log();
+
+        #[cfg(feature = "opentelemetry-support")]
+        self.record_in_active_span();
+    }
+
+    /// Serializes the response body in the given format.
+    pub(crate) fn render_body(
+        &self,
+        format: Format,
+        redaction: Redaction,
+        instance: Option<&str>,
+    ) -> Vec<u8> {
+        let redacted;
+        let err = if redaction.applies_to(self) {
+            redacted = self.redacted();
+            &redacted
+        } else {
+            self
+        };
+
+        let result = match format {
+            Format::Envelope => serde_json::to_vec(err),
+            Format::Problem => {
+                let mut problem = err.to_problem_details();
+                problem.instance = instance.map(String::from);
+                serde_json::to_vec(&problem)
+            }
+        };
+        result.expect("error envelope serialization is infallible")
+    }
+}
@@ -0,0 +1,356 @@
+//! Retrying operations that fail with an `Error`.
+//!
+//! Available with the `tokio-support` feature. [`retry`] re-runs
+//! an operation while it fails with a `retryable` error, waiting the
+//! error's `retry_after` hint when present and exponential backoff with
+//! jitter otherwise.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use error_envelope::{retry, Error, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! # async fn fetch_user() -> Result<String, Error> { Ok(String::new()) }
+//! # async fn run() -> Result<(), Error> {
+//! let policy = RetryPolicy::new()
+//!     .max_attempts(5)
+//!     .deadline(Duration::from_secs(10));
+//!
+//! let user = retry(&policy, || async { fetch_user().await }).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::random::random_u64;
+use crate::Error;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Limits and backoff settings for [`retry`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    deadline: Option<Duration>,
+    max_retry_after: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a policy with 3 attempts and jittered backoff starting at
+    /// 100ms, doubling up to 10s, honoring `retry_after` hints up to 60s.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+            deadline: None,
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+
+    /// Sets the total number of attempts, including the first (at least 1).
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the backoff before the second attempt.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Caps the computed backoff (`retry_after` hints are not capped).
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Sets the factor the backoff grows by after each attempt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `multiplier` is negative, NaN or infinite.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        assert!(
+            multiplier.is_finite() && multiplier >= 0.0,
+            "retry multiplier must be finite and non-negative, got {multiplier}"
+        );
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Enables or disables jitter (on by default).
+    ///
+    /// With jitter, each wait is a random duration between half and all of
+    /// the computed backoff.
+    pub fn jitter(mut self, enabled: bool) -> Self {
+        self.jitter = enabled;
+        self
+    }
+
+    /// Gives up once the next wait would end more than `deadline` after
+    /// the first attempt started.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Gives up instead of waiting when an error's `retry_after` hint is
+    /// longer than `max` (60s by default).
+    ///
+    /// Hints come from the remote side, so this bounds how long a server
+    /// can hold the retry loop.
+    pub fn max_retry_after(mut self, max: Duration) -> Self {
+        self.max_retry_after = max;
+        self
+    }
+
+    /// Returns the backoff before attempt `attempt + 1`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        // Clamp in f64 seconds: the unclamped product can overflow Duration
+        let mut secs = (self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent))
+            .min(self.max_backoff.as_secs_f64());
+        if self.jitter {
+            secs *= 0.5 + random_unit() / 2.0;
+        }
+        Duration::try_from_secs_f64(secs).unwrap_or(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `operation` until it succeeds, fails with a non-retryable error,
+/// or the policy's attempts or deadline run out.
+///
+/// Waits the error's `retry_after` when set (giving up if it exceeds the
+/// policy's `max_retry_after`), and the policy's backoff otherwise. The returned error is the last one, with the number of
+/// attempts made added to its details as `attempts`.
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, mut operation: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let started = Instant::now();
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let err = match operation().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        if !err.retryable || attempt >= policy.max_attempts {
+            return Err(with_attempts(err, attempt));
+        }
+
+        let wait = match err.retry_after {
+            Some(hint) if hint > policy.max_retry_after => {
+                return Err(with_attempts(err, attempt));
+            }
+            Some(hint) => hint,
+            None => policy.backoff(attempt),
+        };
+        if let Some(deadline) = policy.deadline {
+            if wait > deadline.saturating_sub(started.elapsed()) {
+                return Err(with_attempts(err, attempt));
+            }
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Records the attempt count in the error's details.
+///
+/// Non-object details are kept under a `details` key.
+fn with_attempts(mut err: Error, attempts: u32) -> Error {
+    let details = match err.details.take() {
+        Some(serde_json::Value::Object(mut map)) => {
+            map.insert("attempts".to_string(), attempts.into());
+            serde_json::Value::Object(map)
+        }
+        Some(other) => serde_json::json!({"attempts": attempts, "details": other}),
+        None => serde_json::json!({"attempts": attempts}),
+    };
+    err.details = Some(details);
+    err
+}
+
+/// Returns a random number in `[0, 1)` without an RNG dependency.
+fn random_unit() -> f64 {
+    (random_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn fast() -> RetryPolicy {
+        RetryPolicy::new().initial_backoff(Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let calls = Cell::new(0);
+        let result = retry(&fast(), || async {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 {
+                Err(Error::unavailable("warming up"))
+            } else {
+                Ok("ready")
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "ready");
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_on_non_retryable() {
+        let calls = Cell::new(0);
+        let err = retry(&fast(), || async {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(Error::not_found("gone"))
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(err.details.unwrap()["attempts"], 1);
+    }
+
+    #[tokio::test]
+    async fn returns_last_error_with_attempts() {
+        let err = retry(&fast().max_attempts(4), || async {
+            Err::<(), _>(Error::timeout("slow").with_details(serde_json::json!({"host": "db"})))
+        })
+        .await
+        .unwrap_err();
+
+        let details = err.details.unwrap();
+        assert_eq!(details["attempts"], 4);
+        assert_eq!(details["host"], "db");
+    }
+
+    #[tokio::test]
+    async fn honours_retry_after_and_deadline() {
+        let started = std::time::Instant::now();
+        let calls = Cell::new(0);
+        let _ = retry(&fast().max_attempts(2), || async {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(
+                Error::rate_limited("slow down").with_retry_after(Duration::from_millis(30)),
+            )
+        })
+        .await;
+        assert_eq!(calls.get(), 2);
+        assert!(started.elapsed() >= Duration::from_millis(30));
+
+        // Waiting the hint would overrun the deadline, so give up early
+        let calls = Cell::new(0);
+        let err = retry(&fast().deadline(Duration::from_millis(10)), || async {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(Error::rate_limited("slow down").with_retry_after(Duration::from_secs(60)))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(calls.get(), 1);
+        assert_eq!(err.code, crate::Code::RateLimited);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_huge_retry_after() {
+        let hint = Duration::from_secs(u64::MAX);
+
+        // Past the hint cap
+        let calls = Cell::new(0);
+        let err = retry(&fast(), || async {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(Error::rate_limited("slow down").with_retry_after(hint))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(calls.get(), 1);
+        assert_eq!(err.details.unwrap()["attempts"], 1);
+
+        // Within the cap, but the deadline check must not overflow
+        let policy = fast()
+            .max_retry_after(Duration::MAX)
+            .deadline(Duration::from_millis(5));
+        let calls = Cell::new(0);
+        let _ = retry(&policy, || async {
+            calls.set(calls.get() + 1);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Err::<(), _>(Error::rate_limited("slow down").with_retry_after(hint))
+        })
+        .await;
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn backoff_grows_and_caps() {
+        let policy = RetryPolicy::new()
+            .jitter(false)
+            .max_backoff(Duration::from_millis(300));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(300));
+
+        let jittered = RetryPolicy::new().backoff(1);
+        assert!(jittered >= Duration::from_millis(50) && jittered <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn backoff_never_overflows() {
+        let policy = RetryPolicy::new().jitter(false);
+        assert_eq!(policy.backoff(69), Duration::from_secs(10));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(10));
+
+        let jittered = RetryPolicy::new().max_backoff(Duration::MAX).backoff(2000);
+        assert!(jittered > Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn many_attempts_stay_capped() {
+        let policy = fast()
+            .max_attempts(100)
+            .max_backoff(Duration::from_micros(100));
+        let calls = Cell::new(0);
+        let err = retry(&policy, || async {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(Error::unavailable("down"))
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(calls.get(), 100);
+        assert_eq!(err.details.unwrap()["attempts"], 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "retry multiplier")]
+    fn rejects_negative_multiplier() {
+        let _ = RetryPolicy::new().multiplier(-1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "retry multiplier")]
+    fn rejects_non_finite_multiplier() {
+        let _ = RetryPolicy::new().multiplier(f64::NAN);
+    }
+}
@@ -1,6 +1,7 @@
+#[allow(clippy::module_inception)]
 #[cfg(test)]
 mod tests {
-    use crate::{Code, Error};
+    use crate::{Code, Error, UpstreamCode};
     use std::time::Duration;
 
     #[test]
@@ -38,6 +39,10 @@ mod tests {
         let err = Error::unauthorized("missing token");
         assert_eq!(err.code, Code::Unauthorized);
         assert_eq!(err.status, 401);
+
+        let err = Error::unsupported_media_type("expected application/json");
+        assert_eq!(err.code, Code::UnsupportedMediaType);
+        assert_eq!(err.status, 415);
     }
 
     #[test]
@@ -67,12 +72,40 @@ mod tests {
 
     #[test]
     fn test_wrap_with_cause() {
-        let cause = std::io::Error::new(std::io::ErrorKind::Other, "connection refused");
+        let cause = std::io::Error::other("connection refused");
         let err = Error::wrap(Code::Internal, 500, "database connection failed", cause);
 
         assert_eq!(err.code, Code::Internal);
         assert!(err.cause().is_some());
         assert!(err.cause().unwrap().contains("connection refused"));
+
+        // Borrowed causes are accepted; only their message is kept
+        let cause = std::io::Error::other("reset");
+        let err = Error::downstream("db", &cause);
+        assert_eq!(err.cause(), Some("reset"));
+    }
+
+    #[test]
+    fn test_source_chain() {
+        use std::error::Error as _;
+
+        let cause = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let err = Error::internal("cannot open file").with_source(cause);
+        let cloned = err.clone();
+
+        let source = cloned.source().unwrap();
+        let io_err = source.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io_err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(err.find_cause::<std::io::Error>().is_some());
+        assert!(err.find_cause::<std::fmt::Error>().is_none());
+
+        assert!(Error::internal("no cause").source().is_none());
+
+        // Only the message is kept without with_source
+        let cause = std::io::Error::other("denied");
+        let err = Error::internal("cannot open file").with_cause_message(cause);
+        assert_eq!(err.cause(), Some("denied"));
+        assert!(err.source().is_none());
     }
 
     #[test]
@@ -86,6 +119,61 @@ mod tests {
         assert!(err.details.is_some());
     }
 
+    #[test]
+    fn test_propagate_upstream() {
+        let upstream = Error::not_found("user 42 not found")
+            .with_trace_id("up-1")
+            .with_details(serde_json::json!({"user_id": 42}));
+
+        let masked = Error::propagate("users", upstream.clone(), UpstreamCode::Mask);
+        assert_eq!(masked.code, Code::DownstreamError);
+        assert_eq!(masked.status, 502);
+        assert_eq!(masked.message, "Downstream service error");
+        assert!(!masked.retryable);
+        assert!(masked.find_cause::<Error>().is_some());
+
+        assert_eq!(masked.details, Some(serde_json::json!({"hops": ["users"]})));
+        assert_eq!(
+            masked.find_cause::<Error>().unwrap().message,
+            "user 42 not found"
+        );
+
+        let exposed = Error::propagate("users", upstream, UpstreamCode::Expose);
+        assert_eq!(exposed.code, Code::NotFound);
+        assert_eq!(exposed.status, 404);
+        assert_eq!(exposed.message, "user 42 not found");
+        assert!(exposed.trace_id.is_none());
+        assert_eq!(
+            exposed.details,
+            Some(serde_json::json!({
+                "user_id": 42,
+                "upstream": {
+                    "service": "users",
+                    "code": "NOT_FOUND",
+                    "status": 404,
+                    "message": "user 42 not found",
+                    "trace_id": "up-1",
+                },
+                "hops": ["users"],
+            }))
+        );
+
+        // Re-exposed validation failures keep their field errors
+        let mut fields = crate::FieldErrors::new();
+        fields.insert("email", "is invalid");
+        let invalid = crate::validation(fields);
+        let exposed = Error::propagate("signup", invalid, UpstreamCode::Expose);
+        let details = exposed.details.unwrap();
+        assert_eq!(details["fields"]["email"], "is invalid");
+        assert_eq!(details["violations"][0]["path"], "email");
+
+        let slow = Error::timeout("query timed out").with_retry_after(Duration::from_secs(5));
+        let err = Error::propagate("search", slow, UpstreamCode::Mask);
+        assert_eq!(err.code, Code::DownstreamTimeout);
+        assert!(err.retryable);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(5)));
+    }
+
     #[test]
     fn test_display() {
         let err = Error::not_found("user not found");
@@ -108,6 +196,140 @@ mod tests {
         assert_eq!(err.message, "user 123 not found");
     }
 
+    #[test]
+    fn test_envelope_macros() {
+        const QUOTA: crate::CustomCode = crate::CustomCode::new("QUOTA_EXCEEDED", 402, "Quota");
+
+        let err = crate::envelope!(Unavailable);
+        assert_eq!(err.message, "Service unavailable");
+        assert!(err.retryable);
+
+        let err = crate::envelope!(
+            Code::Custom(QUOTA), "{} of {} used", 10, 10;
+            details = serde_json::json!({"limit": 10}),
+            retry_after = Duration::from_secs(60),
+            status = 429
+        );
+        assert_eq!(err.code, Code::Custom(QUOTA));
+        assert_eq!(err.message, "10 of 10 used");
+        assert_eq!(err.status, 429);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(60)));
+        assert_eq!(err.details.unwrap()["limit"], 10);
+
+        fn expensive() -> String {
+            panic!("message formatted for a passing check");
+        }
+        fn check(allowed: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            crate::ensure_envelope!(allowed, Forbidden, "{}", expensive());
+            Ok(())
+        }
+        assert!(check(true).is_ok());
+
+        #[allow(clippy::result_large_err)]
+        fn lookup(id: u32) -> Result<(), Error> {
+            crate::bail_envelope!(NotFound, "user {id} not found"; trace_id = "t-1");
+        }
+        let err = lookup(7).unwrap_err();
+        assert_eq!(err.message, "user 7 not found");
+        assert_eq!(err.trace_id.as_deref(), Some("t-1"));
+    }
+
+    #[test]
+    fn test_result_and_option_ext() {
+        use crate::{OptionExt, ResultExt};
+
+        let parsed: Result<u32, _> = "abc".parse::<u32>();
+        let err = parsed.clone().or_internal("bad config").unwrap_err();
+        assert_eq!(err.code, Code::Internal);
+        assert!(err.find_cause::<std::num::ParseIntError>().is_some());
+
+        let err = parsed.clone().or_code(Code::Conflict, "").unwrap_err();
+        assert_eq!(err.status, 409);
+        assert_eq!(err.message, "Conflict");
+
+        let err = parsed
+            .with_envelope(|e| Error::bad_request(format!("invalid id: {}", e)))
+            .unwrap_err();
+        assert_eq!(err.message, "invalid id: invalid digit found in string");
+        assert!(err.cause().is_some());
+
+        let err = None::<u32>.or_not_found("order 7").unwrap_err();
+        assert_eq!(err.message, "order 7 not found");
+        let err = None::<u32>.or_code(Code::Gone, "").unwrap_err();
+        assert_eq!(err.status, 410);
+        assert_eq!(Some(1).or_not_found("user").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_field_errors() {
+        use crate::{FieldErrors, Violation};
+
+        let mut errors = FieldErrors::new();
+        errors.add("password", "too_short", "must be at least 8 characters");
+        errors.add("password", "missing_digit", "must contain a number");
+        errors.push(
+            Violation::new("/items/10/qty", "must be positive")
+                .with_code("range")
+                .with_param("min", 1),
+        );
+        errors.insert("items[2].qty", "is required");
+        errors.insert("/a~1b", "escaped");
+
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors.get("/items/2/qty"), Some("is required"));
+        assert_eq!(
+            errors.get("password"),
+            Some("must be at least 8 characters")
+        );
+
+        let details = crate::validation(errors).details.unwrap();
+        assert_eq!(
+            details["fields"],
+            serde_json::json!({
+                "a/b": "escaped",
+                "items[10].qty": "must be positive",
+                "items[2].qty": "is required",
+                "password": "must be at least 8 characters",
+            })
+        );
+
+        // Ordered by path (indexes numerically), then insertion
+        let pointers: Vec<_> = details["violations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["pointer"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            pointers,
+            [
+                "/a~1b",
+                "/items/2/qty",
+                "/items/10/qty",
+                "/password",
+                "/password"
+            ]
+        );
+        assert_eq!(
+            details["violations"][2],
+            serde_json::json!({
+                "path": "items[10].qty",
+                "pointer": "/items/10/qty",
+                "code": "range",
+                "message": "must be positive",
+                "params": {"min": 1},
+            })
+        );
+        assert_eq!(details["violations"][4]["code"], "missing_digit");
+
+        // Plain maps still work
+        let mut map = std::collections::HashMap::new();
+        map.insert("email".to_string(), "is invalid".to_string());
+        let details = crate::validation(map).details.unwrap();
+        assert_eq!(details["fields"]["email"], "is invalid");
+        assert!(details["violations"][0].get("code").is_none());
+    }
+
     #[test]
     fn test_default_message() {
         let err = Error::new(Code::Internal, 500, "");
@@ -133,4 +355,381 @@ mod tests {
         assert_eq!(modified.trace_id, Some("trace-456".to_string()));
         assert!(modified.retryable);
     }
+
+    #[test]
+    fn test_into_http_response() {
+        let err = Error::rate_limited("too many requests")
+            .with_trace_id("abc-123")
+            .with_retry_after(Duration::from_secs(30));
+        let response = err.into_http_response();
+
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        assert_eq!(response.headers()["retry-after"], "30");
+        assert_eq!(response.headers()["x-request-id"], "abc-123");
+
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["code"], "RATE_LIMITED");
+        assert_eq!(body["retry_after"], "30s");
+    }
+
+    #[test]
+    fn test_into_http_response_invalid_status() {
+        let err = Error::internal("boom").with_status(1000);
+        let response = err.into_http_response();
+        assert_eq!(response.status(), 500);
+        assert!(!response.headers().contains_key("x-request-id"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let err = Error::rate_limited("too many requests")
+            .with_details(serde_json::json!({"limit": 100}))
+            .with_trace_id("abc-123")
+            .with_retry_after(Duration::from_secs(90));
+
+        let json = serde_json::to_string(&err).unwrap();
+        let parsed: Error = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.code, Code::RateLimited);
+        assert_eq!(parsed.status, 429);
+        assert_eq!(parsed.message, "too many requests");
+        assert_eq!(parsed.details, err.details);
+        assert_eq!(parsed.trace_id, Some("abc-123".to_string()));
+        assert!(parsed.retryable);
+        assert_eq!(parsed.retry_after, Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn test_deserialize_tolerates_unknown_code() {
+        let json = r#"{"code":"SOMETHING_NEW","message":"huh","retryable":true}"#;
+        let parsed: Error = serde_json::from_str(json).unwrap();
+
+        assert_eq!(parsed.code, Code::Internal);
+        assert_eq!(parsed.message, "huh");
+        assert!(parsed.retryable);
+
+        let json = r#"{"code":"SOMETHING_NEW","message":"","status":429}"#;
+        let parsed: Error = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.code, Code::RateLimited);
+        assert_eq!(parsed.status, 429);
+        assert_eq!(parsed.message, "Rate limited");
+        assert!(parsed.retryable);
+
+        let json = r#"{"code":"NOT_FOUND","message":"gone","status":"missing"}"#;
+        let parsed: Error = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.code, Code::NotFound);
+        assert_eq!(parsed.status, 404);
+    }
+
+    #[test]
+    fn test_deserialize_retry_after_formats() {
+        let parsed: Error =
+            serde_json::from_str(r#"{"code":"UNAVAILABLE","message":"","retry_after":"1h2m3s"}"#)
+                .unwrap();
+        assert_eq!(parsed.retry_after, Some(Duration::from_secs(3723)));
+        assert_eq!(parsed.message, "Service unavailable");
+        assert!(parsed.retryable);
+
+        let parsed: Error =
+            serde_json::from_str(r#"{"code":"RATE_LIMITED","retry_after":15}"#).unwrap();
+        assert_eq!(parsed.retry_after, Some(Duration::from_secs(15)));
+
+        let result: Result<Error, _> =
+            serde_json::from_str(r#"{"code":"RATE_LIMITED","retry_after":"soon"}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_problem_details() {
+        let err = Error::validation("email is invalid")
+            .with_details(serde_json::json!({"field": "email"}))
+            .with_trace_id("abc-123");
+        let response = err.into_http_response_as(crate::Format::Problem, |body| body);
+
+        assert_eq!(response.status(), 400);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/problem+json"
+        );
+
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["type"], "about:blank");
+        assert_eq!(body["title"], "Invalid input");
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["detail"], "email is invalid");
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["trace_id"], "abc-123");
+        assert_eq!(body["retryable"], false);
+        assert_eq!(body["details"]["field"], "email");
+        assert!(body.get("instance").is_none());
+    }
+
+    #[test]
+    fn test_problem_details_round_trip() {
+        let problem = Error::conflict("already exists")
+            .with_trace_id("abc-123")
+            .to_problem_details();
+        let json = serde_json::to_string(&problem).unwrap();
+        let parsed: crate::ProblemDetails = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, problem);
+
+        // Unknown or missing codes fall back to the status
+        let json = r#"{"type":"about:blank","title":"Busy","status":503,"detail":"try later","code":"SOMETHING_NEW"}"#;
+        let parsed: crate::ProblemDetails = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.code, Code::Unavailable);
+        assert!(parsed.retryable);
+
+        let json = r#"{"title":"Not found","status":404,"detail":"no such user"}"#;
+        let parsed: crate::ProblemDetails = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.code, Code::NotFound);
+        assert_eq!(parsed.type_uri, "about:blank");
+        assert!(!parsed.retryable);
+    }
+
+    #[test]
+    fn test_code_from_status() {
+        assert_eq!(Code::from_status(401), Code::Unauthorized);
+        assert_eq!(Code::from_status(418), Code::BadRequest);
+        assert_eq!(Code::from_status(504), Code::Timeout);
+        assert_eq!(Code::from_status(500), Code::Internal);
+        assert_eq!(Code::from_status(200), Code::Internal);
+    }
+
+    #[test]
+    fn test_code_wire_names() {
+        for code in Code::BUILTIN {
+            let json = serde_json::to_string(&code).unwrap();
+            assert_eq!(json, format!("\"{}\"", code.as_str()));
+            let parsed: Code = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, code);
+        }
+        assert_eq!(Code::UnprocessableEntity.as_str(), "UNPROCESSABLE_ENTITY");
+        assert!(serde_json::from_str::<Code>("\"NOPE\"").is_err());
+    }
+
+    #[test]
+    fn test_custom_code() {
+        const EMAIL_TAKEN: crate::CustomCode =
+            crate::CustomCode::new("EMAIL_TAKEN", 409, "Email already registered");
+        const QUOTA_EXHAUSTED: crate::CustomCode =
+            crate::CustomCode::new("QUOTA_EXHAUSTED", 429, "Quota exhausted").with_retryable(true);
+
+        let err = Error::new(EMAIL_TAKEN.into(), 0, "");
+        assert_eq!(err.status, 409);
+        assert_eq!(err.message, "Email already registered");
+        assert!(!err.retryable);
+        assert!(crate::is(&err, Code::Custom(EMAIL_TAKEN)));
+        assert!(!crate::is(&err, Code::Conflict));
+
+        let json = serde_json::to_string(&err).unwrap();
+        assert!(json.contains("\"code\":\"EMAIL_TAKEN\""));
+
+        let err = Error::new(QUOTA_EXHAUSTED.into(), 0, "");
+        assert!(err.retryable);
+        assert_eq!(err.into_http_response().status(), 429);
+    }
+
+    #[test]
+    #[should_panic(expected = "built-in code name")]
+    fn test_custom_code_rejects_builtin_name() {
+        let name = String::from("NOT_FOUND");
+        let _ = crate::CustomCode::new(name.leak(), 410, "User gone");
+    }
+
+    #[test]
+    fn test_registered_custom_code_round_trip() {
+        const INSUFFICIENT_FUNDS: crate::CustomCode =
+            crate::CustomCode::new("INSUFFICIENT_FUNDS", 402, "Insufficient funds");
+
+        let json = r#"{"code":"INSUFFICIENT_FUNDS","message":"balance too low"}"#;
+        let parsed: Error = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.code, Code::Internal);
+
+        INSUFFICIENT_FUNDS.register();
+        let parsed: Error = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.code, Code::Custom(INSUFFICIENT_FUNDS));
+        assert_eq!(parsed.status, 402);
+        assert_eq!(parsed.message, "balance too low");
+    }
+
+    #[test]
+    fn test_from_io_error_kinds() {
+        use std::io::{Error as IoError, ErrorKind};
+
+        let cases = [
+            (ErrorKind::TimedOut, Code::Timeout, 504, true),
+            (ErrorKind::NotFound, Code::NotFound, 404, false),
+            (ErrorKind::PermissionDenied, Code::Forbidden, 403, false),
+            (ErrorKind::ConnectionRefused, Code::Unavailable, 503, true),
+            (ErrorKind::Other, Code::Internal, 500, false),
+        ];
+        for (kind, code, status, retryable) in cases {
+            let err = crate::from(IoError::new(kind, "io failure"));
+            assert_eq!(err.code, code, "{:?}", kind);
+            assert_eq!(err.status, status);
+            assert_eq!(err.retryable, retryable);
+            assert_eq!(err.cause(), Some("io failure"));
+        }
+    }
+
+    #[test]
+    fn test_from_serde_json_error() {
+        let json_err = serde_json::from_str::<serde_json::Value>("{oops").unwrap_err();
+        let err = crate::from(json_err);
+        assert_eq!(err.code, Code::BadRequest);
+        assert_eq!(err.status, 400);
+    }
+
+    #[cfg(feature = "tokio-support")]
+    #[tokio::test]
+    async fn test_from_tokio_elapsed() {
+        let elapsed = tokio::time::timeout(Duration::from_millis(1), std::future::pending::<()>())
+            .await
+            .unwrap_err();
+        let err = crate::from(elapsed);
+        assert_eq!(err.code, Code::Timeout);
+        assert!(err.retryable);
+    }
+
+    #[test]
+    fn test_from_heuristics() {
+        #[derive(Debug)]
+        struct Message(&'static str);
+        impl std::fmt::Display for Message {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+        impl std::error::Error for Message {}
+
+        assert_eq!(crate::from(Message("query timed out")).code, Code::Timeout);
+        assert_eq!(
+            crate::from(Message("operation canceled")).code,
+            Code::Canceled
+        );
+        assert_eq!(
+            crate::from(Message("user cancelled subscription")).code,
+            Code::Internal
+        );
+    }
+
+    #[derive(Debug)]
+    struct QuotaExceeded {
+        retry_secs: u64,
+    }
+    impl std::fmt::Display for QuotaExceeded {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "quota exceeded")
+        }
+    }
+    impl std::error::Error for QuotaExceeded {}
+
+    #[test]
+    fn test_classifier_rules_in_order() {
+        let classifier = crate::Classifier::new()
+            .rule(|e: &QuotaExceeded| {
+                Some(
+                    Error::rate_limited("quota exceeded")
+                        .with_retry_after(Duration::from_secs(e.retry_secs)),
+                )
+            })
+            .map::<QuotaExceeded>(Code::Forbidden)
+            .map::<std::io::Error>(Code::Unavailable);
+
+        let err = classifier.classify(QuotaExceeded { retry_secs: 5 });
+        assert_eq!(err.code, Code::RateLimited);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(5)));
+        assert!(err.find_cause::<QuotaExceeded>().is_some());
+
+        // Custom rules take precedence over built-in io classification
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(classifier.classify(io_err).code, Code::Unavailable);
+    }
+
+    #[test]
+    fn test_classifier_matches_source_chain() {
+        #[derive(Debug)]
+        struct Wrapper(QuotaExceeded);
+        impl std::fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "request failed")
+            }
+        }
+        impl std::error::Error for Wrapper {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let outer = Wrapper(QuotaExceeded { retry_secs: 1 });
+
+        let classifier = crate::Classifier::new().map::<QuotaExceeded>(Code::RateLimited);
+        assert_eq!(classifier.classify(outer).code, Code::RateLimited);
+    }
+
+    #[test]
+    fn test_classifier_fallback() {
+        let classifier = crate::Classifier::new()
+            .heuristics(false)
+            .fallback(Code::DownstreamError);
+
+        let err = classifier.classify(std::fmt::Error);
+        assert_eq!(err.code, Code::DownstreamError);
+        assert_eq!(err.status, 502);
+
+        let io_err = std::io::Error::other("timed out");
+        assert_eq!(classifier.classify(io_err).code, Code::DownstreamError);
+    }
+
+    #[test]
+    fn test_global_classifier() {
+        #[derive(Debug)]
+        struct RowNotFound;
+        impl std::fmt::Display for RowNotFound {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "row not found")
+            }
+        }
+        impl std::error::Error for RowNotFound {}
+
+        crate::set_classifier(crate::Classifier::new().map::<RowNotFound>(Code::NotFound));
+        let err = crate::from(RowNotFound);
+        assert_eq!(err.code, Code::NotFound);
+        assert_eq!(err.status, 404);
+    }
+
+    #[test]
+    fn test_redaction_policy() {
+        use crate::{Format, Redaction};
+
+        let err = Error::internal("db: connection to 10.0.0.5 refused")
+            .with_details(serde_json::json!({"host": "10.0.0.5"}))
+            .with_trace_id("abc-123");
+        let body = err.render_body(Format::Envelope, Redaction::ServerErrors, None);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["message"], "Internal error");
+        assert_eq!(json["trace_id"], "abc-123");
+        assert!(json.get("details").is_none());
+
+        let body = err.render_body(Format::Problem, Redaction::ServerErrors, None);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["detail"], "Internal error");
+
+        // Client errors and Redaction::Off pass through untouched
+        let err = Error::not_found("user 42 not found");
+        let body = err.render_body(Format::Envelope, Redaction::ServerErrors, None);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["message"], "user 42 not found");
+        assert!(!Redaction::Off.applies_to(&Error::internal("boom")));
+
+        // Invalid statuses go out as 500, so they are redacted like one
+        let err = Error::internal("secret").with_status(42);
+        assert!(Redaction::ServerErrors.applies_to(&err));
+        let body = err.render_body(Format::Problem, Redaction::ServerErrors, None);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["detail"], "Internal error");
+        assert_eq!(json["status"], 500);
+        assert_eq!(err.into_http_response().status(), 500);
+    }
 }
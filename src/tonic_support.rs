@@ -0,0 +1,234 @@
+//! tonic (gRPC) integration for error-envelope.
+//!
+//! Enable this module with the `tonic-support` feature.
+//!
+//! - `From<Error> for tonic::Status` maps the code to a gRPC code and
+//!   carries the JSON envelope in the `x-error-envelope-bin` metadata
+//!   entry, with the trace ID, code and retry hint also in metadata. The
+//!   status details are left empty for `google.rpc.Status` payloads.
+//! - `From<tonic::Status> for Error` turns a failed downstream call into
+//!   `DOWNSTREAM_ERROR` (or `DOWNSTREAM_TIMEOUT`), keeping the remote trace
+//!   ID, retryability and retry hint.
+//!
+//! # Example
+//!
+//! ```rust
+//! use error_envelope::Error;
+//!
+//! fn get_user(id: &str) -> Result<String, tonic::Status> {
+//!     Err(Error::not_found("user not found").with_trace_id("abc-123"))?
+//! }
+//!
+//! let status = get_user("42").unwrap_err();
+//! assert_eq!(status.code(), tonic::Code::NotFound);
+//! assert_eq!(status.metadata().get("x-request-id").unwrap(), "abc-123");
+//! ```
+
+use crate::response::REQUEST_ID_HEADER;
+use crate::{default_redaction, Code, Error, Format};
+use std::time::Duration;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::Status;
+
+/// Binary metadata key carrying the JSON envelope.
+const ENVELOPE_KEY: &str = "x-error-envelope-bin";
+
+/// Metadata key carrying the envelope code.
+const ERROR_CODE_KEY: &str = "x-error-code";
+
+/// Metadata key carrying the retry hint in seconds.
+const RETRY_AFTER_KEY: &str = "retry-after";
+
+impl Code {
+    /// Returns the gRPC status code for this code.
+    ///
+    /// Custom codes are mapped by their HTTP status.
+    pub fn grpc_code(&self) -> tonic::Code {
+        match self {
+            Code::Internal => tonic::Code::Internal,
+            Code::BadRequest
+            | Code::ValidationFailed
+            | Code::UnprocessableEntity
+            | Code::UnsupportedMediaType => tonic::Code::InvalidArgument,
+            Code::NotFound | Code::Gone => tonic::Code::NotFound,
+            Code::MethodNotAllowed => tonic::Code::Unimplemented,
+            Code::Conflict => tonic::Code::AlreadyExists,
+            Code::PayloadTooLarge | Code::RateLimited => tonic::Code::ResourceExhausted,
+            Code::RequestTimeout | Code::Timeout | Code::DownstreamTimeout => {
+                tonic::Code::DeadlineExceeded
+            }
+            Code::Unavailable | Code::DownstreamError => tonic::Code::Unavailable,
+            Code::Unauthorized => tonic::Code::Unauthenticated,
+            Code::Forbidden => tonic::Code::PermissionDenied,
+            Code::Canceled => tonic::Code::Cancelled,
+            Code::Custom(custom) => grpc_code_for_status(custom.status()),
+        }
+    }
+}
+
+/// Maps an HTTP status to the closest gRPC code.
+fn grpc_code_for_status(status: u16) -> tonic::Code {
+    match status {
+        400 | 415 | 422 => tonic::Code::InvalidArgument,
+        401 => tonic::Code::Unauthenticated,
+        403 => tonic::Code::PermissionDenied,
+        404 | 410 => tonic::Code::NotFound,
+        408 | 504 => tonic::Code::DeadlineExceeded,
+        409 => tonic::Code::AlreadyExists,
+        413 | 429 => tonic::Code::ResourceExhausted,
+        499 => tonic::Code::Cancelled,
+        501 => tonic::Code::Unimplemented,
+        502 | 503 => tonic::Code::Unavailable,
+        400..=499 => tonic::Code::FailedPrecondition,
+        _ => tonic::Code::Internal,
+    }
+}
+
+impl From<Error> for Status {
+    fn from(err: Error) -> Self {
+        err.report();
+
+        let redaction = default_redaction();
+        let message = if redaction.applies_to(&err) {
+            err.redacted().message
+        } else {
+            err.message.clone()
+        };
+        let envelope = err.render_body(Format::Envelope, redaction, None);
+
+        let mut metadata = MetadataMap::new();
+        metadata.insert_bin(ENVELOPE_KEY, MetadataValue::from_bytes(&envelope));
+        metadata.insert(
+            ERROR_CODE_KEY,
+            MetadataValue::from_static(err.code.as_str()),
+        );
+        if let Some(duration) = err.retry_after {
+            metadata.insert(RETRY_AFTER_KEY, duration.as_secs().max(1).into());
+        }
+        if let Some(value) = err
+            .trace_id
+            .as_deref()
+            .and_then(|trace_id| MetadataValue::try_from(trace_id).ok())
+        {
+            metadata.insert(REQUEST_ID_HEADER, value);
+        }
+
+        Status::with_metadata(err.code.grpc_code(), message, metadata)
+    }
+}
+
+impl From<Status> for Error {
+    fn from(status: Status) -> Self {
+        // Envelope from an error-envelope service, if it sent one
+        let metadata = status.metadata();
+        let remote: Option<Error> = metadata
+            .get_bin(ENVELOPE_KEY)
+            .and_then(|value| value.to_bytes().ok())
+            .and_then(|envelope| serde_json::from_slice(&envelope).ok());
+
+        let trace_id = metadata
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+            .or_else(|| remote.as_ref().and_then(|remote| remote.trace_id.clone()));
+        let retry_after = metadata
+            .get(RETRY_AFTER_KEY)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .or_else(|| remote.as_ref().and_then(|remote| remote.retry_after));
+        let retryable = match &remote {
+            Some(remote) => remote.retryable,
+            None => matches!(
+                status.code(),
+                tonic::Code::Unavailable
+                    | tonic::Code::ResourceExhausted
+                    | tonic::Code::DeadlineExceeded
+                    | tonic::Code::Aborted
+            ),
+        };
+
+        let mut details = serde_json::json!({
+            "grpc_code": format!("{:?}", status.code()),
+        });
+        if let Some(remote) = &remote {
+            details["downstream_code"] = remote.code.as_str().into();
+        }
+
+        let code = match status.code() {
+            tonic::Code::DeadlineExceeded => Code::DownstreamTimeout,
+            _ => Code::DownstreamError,
+        };
+        let message = status.message().to_string();
+        let mut err = Error::new(code, 0, message)
+            .with_details(details)
+            .with_retryable(retryable)
+            .with_source(status);
+        err.trace_id = trace_id;
+        err.retry_after = retry_after;
+        err
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_to_status() {
+        let err = Error::rate_limited("slow down")
+            .with_trace_id("abc-123")
+            .with_retry_after(Duration::from_secs(30));
+        let status = Status::from(err);
+
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.message(), "slow down");
+        assert_eq!(status.metadata().get("x-request-id").unwrap(), "abc-123");
+        assert_eq!(
+            status.metadata().get("x-error-code").unwrap(),
+            "RATE_LIMITED"
+        );
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "30");
+
+        assert!(status.details().is_empty());
+        let envelope = status.metadata().get_bin("x-error-envelope-bin").unwrap();
+        let envelope: serde_json::Value =
+            serde_json::from_slice(&envelope.to_bytes().unwrap()).unwrap();
+        assert_eq!(envelope["code"], "RATE_LIMITED");
+        assert_eq!(envelope["retry_after"], "30s");
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        let status = Status::from(
+            Error::unavailable("maintenance")
+                .with_trace_id("abc-123")
+                .with_retry_after(Duration::from_secs(90)),
+        );
+        let err = Error::from(status);
+
+        assert_eq!(err.code, Code::DownstreamError);
+        assert_eq!(err.status, 502);
+        assert_eq!(err.message, "maintenance");
+        assert_eq!(err.trace_id.as_deref(), Some("abc-123"));
+        assert_eq!(err.retry_after, Some(Duration::from_secs(90)));
+        assert!(err.retryable);
+        assert_eq!(
+            err.details.as_ref().unwrap()["downstream_code"],
+            "UNAVAILABLE"
+        );
+        assert!(err.find_cause::<Status>().is_some());
+    }
+
+    #[test]
+    fn test_plain_status() {
+        let err = Error::from(Status::deadline_exceeded("too slow"));
+        assert_eq!(err.code, Code::DownstreamTimeout);
+        assert!(err.retryable);
+
+        let err = Error::from(Status::not_found("no such user"));
+        assert_eq!(err.code, Code::DownstreamError);
+        assert!(!err.retryable);
+        assert_eq!(err.details.as_ref().unwrap()["grpc_code"], "NotFound");
+    }
+}
@@ -0,0 +1,279 @@
+//! Tower middleware for error-envelope.
+//!
+//! Enable this module with the `tower-support` feature (also enabled by
+//! `axum-support`). [`TraceIdLayer`] works with any `tower` service over
+//! `http` requests, such as hyper, tonic or axum servers.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use error_envelope::{tower_support::TraceIdLayer, Error};
+//! use tower::{service_fn, ServiceBuilder};
+//!
+//! let service = ServiceBuilder::new()
+//!     .layer(TraceIdLayer::new())
+//!     .service(service_fn(|_req: http::Request<()>| async {
+//!         // trace_id is filled in automatically
+//!         Ok::<_, std::convert::Infallible>(
+//!             Error::not_found("User not found").into_http_response(),
+//!         )
+//!     }));
+//! ```
+
+use crate::random::random_u64;
+use crate::response::REQUEST_ID_HEADER;
+use http::{HeaderName, HeaderValue};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+tokio::task_local! {
+    static CURRENT_TRACE_ID: TraceId;
+}
+
+/// Returns the trace ID of the request being handled, if inside a
+/// [`TraceIdLayer`].
+pub fn current_trace_id() -> Option<String> {
+    CURRENT_TRACE_ID.try_with(|id| id.0.clone()).ok()
+}
+
+/// Trace ID of the current request, stored in the request extensions by
+/// [`TraceIdLayer`].
+///
+/// Axum handlers can read it with `Extension<TraceId>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceId(String);
+
+impl TraceId {
+    /// Returns the trace ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TraceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Extracts (or generates) a trace ID for every request.
+///
+/// The ID is taken from the W3C `traceparent` header, then from
+/// `X-Request-Id` (or the header set with [`header`](Self::header)), and
+/// generated when neither is present. While the request is handled:
+///
+/// - It is available as a `TraceId` request extension and via
+///   [`current_trace_id`]
+/// - Any `Error` rendered without a trace ID gets this one
+/// - Responses echo it in `X-Request-Id`
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use error_envelope::tower_support::{TraceId, TraceIdLayer};
+/// use tower::{service_fn, ServiceBuilder};
+///
+/// let service = ServiceBuilder::new()
+///     .layer(TraceIdLayer::new().header("x-correlation-id"))
+///     .service(service_fn(|req: http::Request<()>| async move {
+///         let trace_id = req.extensions().get::<TraceId>().unwrap();
+///         Ok::<_, std::convert::Infallible>(http::Response::new(trace_id.to_string()))
+///     }));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TraceIdLayer {
+    header: HeaderName,
+}
+
+impl TraceIdLayer {
+    /// Creates a layer that falls back to the `X-Request-Id` header.
+    pub fn new() -> Self {
+        Self {
+            header: HeaderName::from_static(REQUEST_ID_HEADER),
+        }
+    }
+
+    /// Reads the trace ID from `header` instead of `X-Request-Id` when no
+    /// `traceparent` is present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `header` is not a valid header name.
+    pub fn header(mut self, header: &str) -> Self {
+        self.header = HeaderName::try_from(header).expect("invalid trace ID header name");
+        self
+    }
+
+    fn extract<B>(&self, req: &http::Request<B>) -> TraceId {
+        let headers = req.headers();
+        let id = headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_traceparent)
+            .or_else(|| {
+                headers
+                    .get(&self.header)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(String::from)
+            })
+            .unwrap_or_else(generate_trace_id);
+        TraceId(id)
+    }
+}
+
+impl Default for TraceIdLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for TraceIdLayer {
+    type Service = TraceIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceIdService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`TraceIdLayer`].
+#[derive(Debug, Clone)]
+pub struct TraceIdService<S> {
+    inner: S,
+    layer: TraceIdLayer,
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for TraceIdService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let trace_id = self.layer.extract(&req);
+        req.extensions_mut().insert(trace_id.clone());
+        let future = CURRENT_TRACE_ID.scope(trace_id.clone(), self.inner.call(req));
+
+        Box::pin(async move {
+            let mut response = future.await?;
+            if let Ok(value) = HeaderValue::from_str(trace_id.as_str()) {
+                response
+                    .headers_mut()
+                    .entry(REQUEST_ID_HEADER)
+                    .or_insert(value);
+            }
+            Ok(response)
+        })
+    }
+}
+
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Returns the trace-id field of a W3C `traceparent` header.
+fn parse_traceparent(value: &str) -> Option<String> {
+    let mut parts = value.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let parent_id = parts.next()?;
+    let flags = parts.next()?;
+
+    let is_hex = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    };
+    let valid = is_hex(version, 2)
+        && version != "ff"
+        && is_hex(trace_id, 32)
+        && is_hex(parent_id, 16)
+        && is_hex(flags, 2)
+        && trace_id.bytes().any(|b| b != b'0');
+    valid.then(|| trace_id.to_string())
+}
+
+/// Generates a random 32-hex-digit ID in the W3C trace-id format.
+fn generate_trace_id() -> String {
+    format!("{:016x}{:016x}", random_u64(), random_u64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+
+    async fn handler(req: http::Request<()>) -> Result<http::Response<Vec<u8>>, Infallible> {
+        let trace_id = req.extensions().get::<TraceId>().unwrap();
+        assert_eq!(current_trace_id().as_deref(), Some(trace_id.as_str()));
+        Ok(Error::not_found("user not found").into_http_response())
+    }
+
+    #[tokio::test]
+    async fn test_trace_id_layer_fills_errors() {
+        let service = TraceIdLayer::new().layer(service_fn(handler));
+
+        let request = http::Request::builder()
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .body(())
+            .unwrap();
+        let response = service.oneshot(request).await.unwrap();
+
+        assert_eq!(
+            response.headers()["x-request-id"],
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        let json: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(json["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    }
+
+    #[tokio::test]
+    async fn test_trace_id_layer_header_and_generation() {
+        let service = TraceIdLayer::new()
+            .header("x-correlation-id")
+            .layer(service_fn(handler));
+
+        let request = http::Request::builder()
+            .header("x-correlation-id", "corr-42")
+            .body(())
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["x-request-id"], "corr-42");
+
+        let request = http::Request::builder().body(()).unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        let generated = response.headers()["x-request-id"].to_str().unwrap();
+        assert_eq!(generated.len(), 32);
+        assert_ne!(generated, generate_trace_id());
+    }
+
+    #[test]
+    fn test_parse_traceparent() {
+        assert_eq!(
+            parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(
+            parse_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(parse_traceparent("garbage").is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! Structured logging of error responses via `tracing`.
+//!
+//! Enable this module with the `tracing-support` feature. Every `Error`
+//! rendered into a response (through `into_http_response*` or the Axum
+//! `IntoResponse` impl) emits one event with the envelope fields:
+//!
+//! - `code`, `status`, `trace_id`, `retryable`
+//! - `cause` (the underlying error message, if any)
+//! - `details` (as JSON)
+//!
+//! The level comes from [`default_level`] unless replaced with
+//! [`set_level_fn`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use error_envelope::{tracing_support, Error};
+//! use tracing::Level;
+//!
+//! // Log 4xx at DEBUG instead, keep 5xx at ERROR
+//! tracing_support::set_level_fn(|err: &Error| {
+//!     Some(if err.status >= 500 { Level::ERROR } else { Level::DEBUG })
+//! });
+//! ```
+
+use crate::Error;
+use std::sync::RwLock;
+use tracing::Level;
+
+/// Maps an error to the level it is logged at; `None` skips logging.
+pub type LevelFn = fn(&Error) -> Option<Level>;
+
+static LEVEL_FN: RwLock<Option<LevelFn>> = RwLock::new(None);
+
+/// Replaces the level mapping used for response events.
+pub fn set_level_fn(level_fn: LevelFn) {
+    let mut current = LEVEL_FN.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(level_fn);
+}
+
+/// Default level mapping.
+///
+/// - 5xx → `ERROR`
+/// - 401, 403 and 429 → `WARN` (auth and abuse signals)
+/// - Other 4xx → `INFO`
+/// - Anything else → `DEBUG`
+///
+/// Uses the status the response is sent with, so an invalid `status` counts
+/// as 500.
+pub fn default_level(err: &Error) -> Option<Level> {
+    Some(match err.response_status().as_u16() {
+        500..=599 => Level::ERROR,
+        401 | 403 | 429 => Level::WARN,
+        400..=499 => Level::INFO,
+        _ => Level::DEBUG,
+    })
+}
+
+impl Error {
+    /// Emits a `tracing` event describing this error.
+    ///
+    /// Called automatically when the error is turned into a response; call
+    /// it directly to log errors handled elsewhere.
+    pub fn log(&self) {
+        let level_fn = LEVEL_FN
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .unwrap_or(default_level);
+        let Some(level) = level_fn(self) else {
+            return;
+        };
+
+        let status = self.response_status().as_u16();
+        let details = self.details.as_ref().map(|details| details.to_string());
+
+        macro_rules! emit {
+            ($level:expr) => {
+                tracing::event!(
+                    target: "error_envelope",
+                    $level,
+                    code = self.code.as_str(),
+                    status,
+                    trace_id = self.trace_id.as_deref(),
+                    retryable = self.retryable,
+                    cause = self.cause(),
+                    details = details.as_deref(),
+                    "{}",
+                    self.message
+                )
+            };
+        }
+
+        match level {
+            Level::ERROR => emit!(Level::ERROR),
+            Level::WARN => emit!(Level::WARN),
+            Level::INFO => emit!(Level::INFO),
+            Level::DEBUG => emit!(Level::DEBUG),
+            _ => emit!(Level::TRACE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    type Captured = Vec<(Level, Vec<(String, String)>)>;
+
+    /// Records the level and fields of every event.
+    #[derive(Clone, Default)]
+    struct Capture {
+        events: Arc<Mutex<Captured>>,
+    }
+
+    struct Fields(Vec<(String, String)>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(Vec::new());
+            event.record(&mut fields);
+            let level = *event.metadata().level();
+            self.events.lock().unwrap().push((level, fields.0));
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn logs_response_with_fields() {
+        let capture = Capture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            let cause = std::io::Error::other("disk full");
+            let _ = Error::internal("write failed")
+                .with_cause_message(cause)
+                .with_trace_id("abc-123")
+                .with_details(serde_json::json!({"path": "/tmp"}))
+                .into_http_response();
+        });
+
+        let events = capture.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        let (level, fields) = &events[0];
+        assert_eq!(*level, Level::ERROR);
+        assert_eq!(field(fields, "code"), Some("INTERNAL"));
+        assert_eq!(field(fields, "status"), Some("500"));
+        assert_eq!(field(fields, "trace_id"), Some("abc-123"));
+        assert_eq!(field(fields, "retryable"), Some("false"));
+        assert_eq!(field(fields, "cause"), Some("disk full"));
+        assert_eq!(field(fields, "details"), Some(r#"{"path":"/tmp"}"#));
+    }
+
+    #[test]
+    fn default_levels() {
+        assert_eq!(default_level(&Error::internal("")), Some(Level::ERROR));
+        assert_eq!(default_level(&Error::rate_limited("")), Some(Level::WARN));
+        assert_eq!(default_level(&Error::not_found("")), Some(Level::INFO));
+        assert_eq!(
+            default_level(&Error::not_found("").with_status(42)),
+            Some(Level::ERROR)
+        );
+    }
+
+    #[test]
+    fn logs_sent_status_for_invalid_status() {
+        let capture = Capture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            let _ = Error::not_found("gone")
+                .with_status(42)
+                .into_http_response();
+        });
+
+        let events = capture.events.lock().unwrap();
+        let (level, fields) = &events[0];
+        assert_eq!(*level, Level::ERROR);
+        assert_eq!(field(fields, "status"), Some("500"));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{Code, Error};
+use serde_json::{Map, Value};
+
+/// Whether a propagated error keeps the upstream service's code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UpstreamCode {
+    /// Report `DOWNSTREAM_ERROR` (502) or `DOWNSTREAM_TIMEOUT` (504) with
+    /// the default message. Only the service chain goes on the wire; the
+    /// upstream envelope is kept as the source for logs.
+    #[default]
+    Mask,
+    /// Re-expose the upstream code, status, message and details, e.g. to
+    /// pass a `NOT_FOUND` from a backing service through unchanged.
+    Expose,
+}
+
+impl Error {
+    /// Creates an error for a failed call whose response was itself an
+    /// envelope.
+    ///
+    /// `details.hops` lists the services the failure passed through,
+    /// nearest first. With [`UpstreamCode::Expose`], the upstream details
+    /// are carried over and the upstream envelope (service, code, status,
+    /// message and trace ID) is added as `details.upstream`, nesting any
+    /// upstream it exposed in turn. With [`UpstreamCode::Mask`], none of
+    /// that reaches the client. Either way retry hints are carried over
+    /// and the upstream error is kept as the source.
+    ///
+    /// # Example
+    /// ```
+    /// use error_envelope::{Code, Error, UpstreamCode};
+    ///
+    /// // What the ledger service returned to payments, and payments to us
+    /// let ledger = Error::unavailable("ledger offline").with_trace_id("t-2");
+    /// let payments = Error::propagate("ledger", ledger, UpstreamCode::Expose);
+    /// let err = Error::propagate("payments", payments, UpstreamCode::Expose);
+    ///
+    /// assert_eq!(err.code, Code::Unavailable);
+    /// let details = err.details.unwrap();
+    /// assert_eq!(details["hops"], serde_json::json!(["payments", "ledger"]));
+    /// assert_eq!(details["upstream"]["upstream"]["trace_id"], "t-2");
+    ///
+    /// // Masking keeps only the hops on the wire
+    /// let ledger = Error::unavailable("ledger offline");
+    /// let err = Error::propagate("ledger", ledger, UpstreamCode::Mask);
+    /// assert_eq!(err.code, Code::DownstreamError);
+    /// assert_eq!(err.details.unwrap(), serde_json::json!({"hops": ["ledger"]}));
+    /// ```
+    pub fn propagate(service: impl Into<String>, upstream: Error, policy: UpstreamCode) -> Self {
+        let service = service.into();
+        let upstream_details = upstream.details.as_ref().and_then(Value::as_object);
+
+        let mut hops = vec![Value::from(service.clone())];
+        if let Some(Value::Array(upstream_hops)) = upstream_details.and_then(|d| d.get("hops")) {
+            hops.extend(upstream_hops.iter().cloned());
+        }
+
+        let mut err = match policy {
+            UpstreamCode::Expose => {
+                let mut nested = Map::new();
+                nested.insert("service".to_string(), service.into());
+                nested.insert("code".to_string(), upstream.code.as_str().into());
+                nested.insert("status".to_string(), upstream.status.into());
+                nested.insert("message".to_string(), upstream.message.clone().into());
+                if let Some(trace_id) = &upstream.trace_id {
+                    nested.insert("trace_id".to_string(), trace_id.clone().into());
+                }
+
+                // Keep the upstream's own details, e.g. validation `fields`
+                let mut details = match &upstream.details {
+                    Some(Value::Object(map)) => map.clone(),
+                    Some(other) => Map::from_iter([("details".to_string(), other.clone())]),
+                    None => Map::new(),
+                };
+                if let Some(deeper) = details.remove("upstream") {
+                    nested.insert("upstream".to_string(), deeper);
+                }
+                details.insert("upstream".to_string(), nested.into());
+                details.insert("hops".to_string(), hops.into());
+
+                Error::new(upstream.code, upstream.status, upstream.message.clone())
+                    .with_details(details.into())
+            }
+            UpstreamCode::Mask => {
+                let code = match upstream.code {
+                    Code::Timeout | Code::DownstreamTimeout | Code::RequestTimeout => {
+                        Code::DownstreamTimeout
+                    }
+                    _ => Code::DownstreamError,
+                };
+                Error::new(code, 0, "").with_details(serde_json::json!({"hops": hops}))
+            }
+        };
+        err.retryable = upstream.retryable;
+        err.retry_after = upstream.retry_after;
+
+        err.with_source(upstream)
+    }
+}
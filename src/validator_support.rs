@@ -0,0 +1,288 @@
+//! validator crate integration for error-envelope.
+//!
+//! Enable this module with the `validator-support` feature.
+//!
+//! - `From<validator::ValidationErrors>` for [`FieldErrors`] and `Error`,
+//!   flattening nested structs (`address.zip`) and lists (`items[2].qty`)
+//!   into `VALIDATION_FAILED` details with per-field codes, messages and
+//!   params.
+//! - With `axum-support`, [`ValidatedJson`] deserializes and validates a
+//!   request body in one step.
+//!
+//! The `value` param validator records for most checks is dropped, so
+//! rejected input (passwords, tokens) is never echoed back to clients.
+//!
+//! # Example
+//!
+//! ```rust
+//! use error_envelope::{Code, Error};
+//! use validator::Validate;
+//!
+//! #[derive(Validate)]
+//! struct SignUp {
+//!     #[validate(length(min = 8, message = "must be at least 8 characters"))]
+//!     password: String,
+//! }
+//!
+//! fn sign_up(form: &SignUp) -> Result<(), Error> {
+//!     form.validate()?;
+//!     Ok(())
+//! }
+//!
+//! let err = sign_up(&SignUp { password: "short".into() }).unwrap_err();
+//! let details = err.details.unwrap();
+//! assert_eq!(err.code, Code::ValidationFailed);
+//! assert_eq!(details["violations"][0]["code"], "length");
+//! assert_eq!(details["violations"][0]["params"]["min"], 8);
+//! ```
+
+use crate::{Error, FieldErrors, Violation};
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+impl From<ValidationErrors> for FieldErrors {
+    fn from(errors: ValidationErrors) -> Self {
+        field_errors(&errors)
+    }
+}
+
+impl From<ValidationErrors> for Error {
+    fn from(errors: ValidationErrors) -> Self {
+        crate::validation(field_errors(&errors)).with_source(errors)
+    }
+}
+
+/// Flattens validator's nested error tree.
+fn field_errors(errors: &ValidationErrors) -> FieldErrors {
+    let mut fields = FieldErrors::new();
+    for (field, kind) in errors.errors() {
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                fields.extend(errors.iter().map(|err| violation(field, err)));
+            }
+            ValidationErrorsKind::Struct(nested) => {
+                fields.nest(field, field_errors(nested));
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    fields.nest(format!("{}[{}]", field, index), field_errors(nested));
+                }
+            }
+        }
+    }
+    fields
+}
+
+fn violation(field: &str, err: &ValidationError) -> Violation {
+    let message = match &err.message {
+        Some(message) => message.to_string(),
+        None => default_message(&err.code).to_string(),
+    };
+
+    let mut violation = Violation::new(field, message).with_code(err.code.as_ref());
+    for (name, value) in &err.params {
+        if name != "value" {
+            violation = violation.with_param(name.as_ref(), value.clone());
+        }
+    }
+    violation
+}
+
+/// Message for checks declared without a `message`.
+fn default_message(code: &str) -> &'static str {
+    match code {
+        "required" => "is required",
+        "email" => "must be a valid email address",
+        "url" => "must be a valid URL",
+        "length" => "has an invalid length",
+        "range" => "is out of range",
+        "must_match" => "does not match",
+        _ => "is invalid",
+    }
+}
+
+#[cfg(feature = "axum-support")]
+pub use self::axum_extractor::ValidatedJson;
+
+#[cfg(feature = "axum-support")]
+mod axum_extractor {
+    use crate::Error;
+    use axum::extract::{rejection::JsonRejection, FromRequest, Request};
+    use validator::Validate;
+
+    /// Axum extractor that deserializes a JSON body and validates it.
+    ///
+    /// Body errors reject like [`axum_support::Json`](crate::axum_support::Json);
+    /// validation failures reject with a `VALIDATION_FAILED` envelope.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use error_envelope::validator_support::ValidatedJson;
+    /// use serde::Deserialize;
+    /// use validator::Validate;
+    ///
+    /// #[derive(Deserialize, Validate)]
+    /// struct CreateUser {
+    ///     #[validate(email)]
+    ///     email: String,
+    /// }
+    ///
+    /// async fn create(ValidatedJson(user): ValidatedJson<CreateUser>) -> String {
+    ///     user.email
+    /// }
+    /// ```
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct ValidatedJson<T>(pub T);
+
+    impl<T> std::ops::Deref for ValidatedJson<T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> std::ops::DerefMut for ValidatedJson<T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.0
+        }
+    }
+
+    #[axum::async_trait]
+    impl<T, S> FromRequest<S> for ValidatedJson<T>
+    where
+        T: Validate,
+        axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+        S: Send + Sync,
+    {
+        type Rejection = Error;
+
+        async fn from_request(req: Request, state: &S) -> Result<Self, Error> {
+            let crate::axum_support::Json(value) =
+                crate::axum_support::Json::<T>::from_request(req, state).await?;
+            value.validate()?;
+            Ok(ValidatedJson(value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Address {
+        #[validate(length(min = 5, max = 10))]
+        zip: String,
+    }
+
+    #[derive(Validate)]
+    struct Item {
+        #[validate(range(min = 1, message = "must be positive"))]
+        qty: i32,
+    }
+
+    #[derive(Validate)]
+    struct Order {
+        #[validate(email, length(min = 6))]
+        email: String,
+        #[validate(nested)]
+        address: Address,
+        #[validate(nested)]
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn flattens_nested_errors() {
+        let order = Order {
+            email: "a.b".to_string(),
+            address: Address {
+                zip: "123".to_string(),
+            },
+            items: vec![Item { qty: 1 }, Item { qty: 0 }],
+        };
+        let err = Error::from(order.validate().unwrap_err());
+
+        assert_eq!(err.code, Code::ValidationFailed);
+        assert_eq!(err.status, 400);
+
+        let details = err.details.unwrap();
+        assert_eq!(details["fields"]["items[1].qty"], "must be positive");
+        assert_eq!(
+            details["violations"],
+            serde_json::json!([
+                {
+                    "path": "address.zip",
+                    "pointer": "/address/zip",
+                    "code": "length",
+                    "message": "has an invalid length",
+                    "params": {"max": 10, "min": 5},
+                },
+                {
+                    "path": "email",
+                    "pointer": "/email",
+                    "code": "length",
+                    "message": "has an invalid length",
+                    "params": {"min": 6},
+                },
+                {
+                    "path": "email",
+                    "pointer": "/email",
+                    "code": "email",
+                    "message": "must be a valid email address",
+                },
+                {
+                    "path": "items[1].qty",
+                    "pointer": "/items/1/qty",
+                    "code": "range",
+                    "message": "must be positive",
+                    "params": {"min": 1},
+                },
+            ])
+        );
+    }
+
+    #[cfg(feature = "axum-support")]
+    #[tokio::test]
+    async fn validated_json_rejects() {
+        use axum::{body::Body, http::Request, routing::post, Router};
+        use serde::Deserialize;
+        use tower::ServiceExt;
+
+        #[derive(Deserialize, Validate)]
+        struct CreateUser {
+            #[validate(email)]
+            email: String,
+        }
+
+        async fn create(ValidatedJson(user): ValidatedJson<CreateUser>) -> String {
+            user.email
+        }
+
+        let app = Router::new().route("/", post(create));
+        let request = |body: &str| {
+            Request::post("/")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request(r#"{"email":"nope"}"#)).await;
+        let response = response.unwrap();
+        assert_eq!(response.status(), 400);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "VALIDATION_FAILED");
+        assert_eq!(json["details"]["violations"][0]["code"], "email");
+
+        let response = app.clone().oneshot(request("{")).await.unwrap();
+        assert_eq!(response.status(), 400);
+
+        let response = app.oneshot(request(r#"{"email":"a@b.co"}"#)).await.unwrap();
+        assert_eq!(response.status(), 200);
+    }
+}
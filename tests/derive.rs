@@ -0,0 +1,124 @@
+#![cfg(feature = "derive")]
+
+use error_envelope::{Code, CustomCode, Error, IntoEnvelope};
+use thiserror::Error as ThisError;
+
+const EMAIL_TAKEN: CustomCode = CustomCode::new("EMAIL_TAKEN", 409, "Email already registered");
+
+#[derive(Debug, ThisError, IntoEnvelope)]
+enum UserError {
+    #[error("user {id} not found")]
+    #[envelope(code = NotFound, message = "user {id} not found", details(id))]
+    NotFound { id: u64 },
+
+    #[error("user {0} deleted")]
+    #[envelope(code = Gone, status = 404, message = "user {0} deleted")]
+    Deleted(u64),
+
+    #[error("email taken")]
+    #[envelope(code = EMAIL_TAKEN)]
+    EmailTaken,
+
+    #[error("database error")]
+    #[envelope(code = Unavailable, message = "database unavailable", retryable = false)]
+    Database(#[from] std::io::Error),
+}
+
+#[derive(Debug, IntoEnvelope)]
+#[envelope(code = RateLimited, message = "slow down", details(limit))]
+struct QuotaExceeded {
+    limit: u32,
+}
+
+#[derive(Debug, IntoEnvelope)]
+#[envelope(code = Internal)]
+enum Fallback {
+    Unknown,
+    #[envelope(code = Forbidden, retryable)]
+    Denied {
+        #[envelope(cause)]
+        reason: std::fmt::Error,
+    },
+}
+
+#[derive(Debug, IntoEnvelope)]
+enum StoreError {
+    #[envelope(code = Unavailable, message = "store failed: {err}")]
+    Db {
+        #[envelope(cause)]
+        err: std::io::Error,
+    },
+
+    #[envelope(code = BadRequest, details(err))]
+    Rejected { err: String },
+}
+
+#[test]
+fn maps_named_fields_and_details() {
+    let err: Error = UserError::NotFound { id: 42 }.into();
+    assert_eq!(err.code, Code::NotFound);
+    assert_eq!(err.status, 404);
+    assert_eq!(err.message, "user 42 not found");
+    assert_eq!(err.details, Some(serde_json::json!({"id": 42})));
+}
+
+#[test]
+fn maps_tuple_fields_and_status_override() {
+    let err: Error = UserError::Deleted(7).into();
+    assert_eq!(err.code, Code::Gone);
+    assert_eq!(err.status, 404);
+    assert_eq!(err.message, "user 7 deleted");
+}
+
+#[test]
+fn maps_custom_codes() {
+    let err: Error = UserError::EmailTaken.into();
+    assert_eq!(err.code, Code::Custom(EMAIL_TAKEN));
+    assert_eq!(err.status, 409);
+    assert_eq!(err.message, "Email already registered");
+}
+
+#[test]
+fn attaches_cause() {
+    let io_err = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+    let err: Error = UserError::Database(io_err).into();
+    assert_eq!(err.code, Code::Unavailable);
+    assert!(!err.retryable);
+    assert_eq!(err.cause(), Some("refused"));
+    assert!(err.find_cause::<std::io::Error>().is_some());
+
+    let err: Error = Fallback::Denied {
+        reason: std::fmt::Error,
+    }
+    .into();
+    assert_eq!(err.code, Code::Forbidden);
+    assert!(err.retryable);
+    assert!(err.find_cause::<std::fmt::Error>().is_some());
+}
+
+#[test]
+fn applies_container_attribute() {
+    let err: Error = QuotaExceeded { limit: 100 }.into();
+    assert_eq!(err.code, Code::RateLimited);
+    assert!(err.retryable);
+    assert_eq!(err.details, Some(serde_json::json!({"limit": 100})));
+
+    let err: Error = Fallback::Unknown.into();
+    assert_eq!(err.code, Code::Internal);
+    assert_eq!(err.message, "Internal error");
+}
+
+#[test]
+fn allows_field_named_err() {
+    let io_err = std::io::Error::other("disk full");
+    let err: Error = StoreError::Db { err: io_err }.into();
+    assert_eq!(err.code, Code::Unavailable);
+    assert_eq!(err.message, "store failed: disk full");
+    assert!(err.find_cause::<std::io::Error>().is_some());
+
+    let err: Error = StoreError::Rejected {
+        err: "bad key".to_string(),
+    }
+    .into();
+    assert_eq!(err.details, Some(serde_json::json!({"err": "bad key"})));
+}